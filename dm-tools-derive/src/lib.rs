@@ -1,58 +1,274 @@
 extern crate proc_macro;
 
 use crate::proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use syn;
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Error, Result};
 
-#[proc_macro_derive(Unit)]
+#[proc_macro_derive(Unit, attributes(unit))]
 pub fn unit_macro_derive(input: TokenStream) -> TokenStream {
     // Construct a representation of Rust code as a syntax tree
     // that we can manipulate
-    let ast = syn::parse(input).unwrap();
+    let ast = parse_macro_input!(input as syn::DeriveInput);
 
     // Build the trait implementation
     impl_unit_macro(&ast)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// Values given through `#[unit(...)]` on a variant.
+#[derive(Default)]
+struct UnitAttrs {
+    value: Option<i64>,
+    name: Option<String>,
+    short: Option<String>,
+    plural: Option<String>,
+}
+
+fn parse_attrs(attrs: &[syn::Attribute]) -> Result<UnitAttrs> {
+    let mut parsed = UnitAttrs::default();
+    for attr in attrs.iter().filter(|a| a.path.is_ident("unit")) {
+        let list = match attr.parse_meta()? {
+            syn::Meta::List(list) => list,
+            meta => {
+                return Err(Error::new_spanned(
+                    meta,
+                    "expected #[unit(key = value, ...)]",
+                ))
+            }
+        };
+        for nested in list.nested.iter() {
+            let (key, lit) = match nested {
+                syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) => (&nv.path, &nv.lit),
+                _ => return Err(Error::new_spanned(nested, "expected `key = value`")),
+            };
+            match lit {
+                syn::Lit::Int(int) if key.is_ident("value") => {
+                    parsed.value = Some(int.base10_parse()?)
+                }
+                syn::Lit::Str(s) if key.is_ident("name") => parsed.name = Some(s.value()),
+                syn::Lit::Str(s) if key.is_ident("short") => parsed.short = Some(s.value()),
+                syn::Lit::Str(s) if key.is_ident("plural") => parsed.plural = Some(s.value()),
+                _ if key.is_ident("value") => {
+                    return Err(Error::new_spanned(lit, "expected an integer value"))
+                }
+                _ if key.is_ident("name") || key.is_ident("short") || key.is_ident("plural") => {
+                    return Err(Error::new_spanned(lit, "expected a string"))
+                }
+                _ => return Err(Error::new_spanned(
+                    key,
+                    "unknown unit attribute, expected one of `value`, `name`, `short`, `plural`",
+                )),
+            }
+        }
+    }
+    Ok(parsed)
+}
+
+/// Whether the enum asks for `#[unit(divisible)]` to be checked.
+fn parse_enum_attrs(attrs: &[syn::Attribute]) -> Result<bool> {
+    let mut divisible = false;
+    for attr in attrs.iter().filter(|a| a.path.is_ident("unit")) {
+        let list = match attr.parse_meta()? {
+            syn::Meta::List(list) => list,
+            meta => return Err(Error::new_spanned(meta, "expected #[unit(divisible)]")),
+        };
+        for nested in list.nested.iter() {
+            match nested {
+                syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("divisible") => {
+                    divisible = true
+                }
+                _ => {
+                    return Err(Error::new_spanned(
+                        nested,
+                        "unknown unit attribute, expected `divisible`",
+                    ))
+                }
+            }
+        }
+    }
+    Ok(divisible)
+}
+
+/// Value of a variant, from `#[unit(value = ...)]` or its discriminant.
+fn variant_value(variant: &syn::Variant, attrs: &UnitAttrs) -> Result<i64> {
+    if let Some(value) = attrs.value {
+        return Ok(value);
+    }
+    discriminant(variant)?.ok_or_else(|| {
+        Error::new_spanned(
+            &variant.ident,
+            "unit variants need a value, e.g. `Gold = 100` or #[unit(value = 100)]",
+        )
+    })
+}
+
+/// Explicit discriminant of a variant, if it has one.
+fn discriminant(variant: &syn::Variant) -> Result<Option<i64>> {
+    match &variant.discriminant {
+        Some((
+            _,
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Int(int),
+                ..
+            }),
+        )) => Ok(Some(int.base10_parse()?)),
+        Some((_, expr)) => Err(Error::new_spanned(
+            expr,
+            "unit discriminants must be integer literals, use #[unit(value = ...)] instead",
+        )),
+        None => Ok(None),
+    }
 }
 
 // Inspired by https://github.com/Peternator7/strum/blob/master/strum_macros/src/macros/enum_iter.rs
-fn impl_unit_macro(ast: &syn::DeriveInput) -> TokenStream {
+fn impl_unit_macro(ast: &syn::DeriveInput) -> Result<TokenStream2> {
     let name = &ast.ident;
-    let variants = match ast.data {
-        syn::Data::Enum(ref v) => &v.variants,
-        _ => panic!("Unit only works on Enums"),
+    let variants = match &ast.data {
+        syn::Data::Enum(data) => &data.variants,
+        syn::Data::Struct(data) => {
+            return Err(Error::new(
+                data.struct_token.span(),
+                "Unit can only be derived for enums",
+            ))
+        }
+        syn::Data::Union(data) => {
+            return Err(Error::new(
+                data.union_token.span(),
+                "Unit can only be derived for enums",
+            ))
+        }
     };
+    let require_divisible = parse_enum_attrs(&ast.attrs)?;
+    if variants.is_empty() {
+        return Err(Error::new_spanned(
+            name,
+            "Unit requires at least one variant",
+        ));
+    }
 
     let mut values = Vec::new();
+    let mut unit_values = Vec::new();
+    let mut names = Vec::new();
+    let mut shorts = Vec::new();
+    let mut plurals = Vec::new();
+    let mut previous: Option<(i64, &syn::Ident)> = None;
+    let mut previous_discriminant: Option<(i64, &syn::Ident)> = None;
+    let mut divisible = true;
     for variant in variants.iter() {
-        use syn::Fields::*;
         let ident = &variant.ident;
-        let params = match variant.fields {
-            Unit => quote! {},
-            _ => panic!("Unit requires simple Enums."), // Unnamed(ref fields) => {
-                                                        //     let defaults = ::std::iter::repeat(quote!(::std::default::Default::default()))
-                                                        //         .take(fields.unnamed.len());
-                                                        //     quote! { (#(#defaults),*) }
-                                                        // }
-                                                        // Named(ref fields) => {
-                                                        //     let fields = fields
-                                                        //         .named
-                                                        //         .iter()
-                                                        //         .map(|field| field.ident.as_ref().unwrap());
-                                                        //     quote! { {#(#fields: ::std::default::Default::default()),*} }
-                                                        // }
+        if !matches!(variant.fields, syn::Fields::Unit) {
+            return Err(Error::new_spanned(
+                &variant.fields,
+                "Unit variants can't hold data",
+            ));
+        }
+        let unit = quote! { #name::#ident };
+
+        // Names default to the variant.
+        let attrs = parse_attrs(&variant.attrs)?;
+        let value = variant_value(variant, &attrs)?;
+        if value <= 0 {
+            return Err(Error::new_spanned(ident, "unit values must be positive"));
+        }
+        // Counters rely on the variants going from smallest to largest.
+        match previous {
+            Some((prev, prev_ident)) if prev == value => {
+                return Err(Error::new_spanned(
+                    ident,
+                    format!("`{}` has the same value as `{}`", ident, prev_ident),
+                ))
+            }
+            Some((prev, prev_ident)) if prev > value => {
+                return Err(Error::new_spanned(
+                    ident,
+                    format!(
+                    "unit variants must be sorted by value, `{}` ({}) is smaller than `{}` ({})",
+                    ident, value, prev_ident, prev
+                ),
+                ))
+            }
+            Some((prev, prev_ident)) if value % prev != 0 => {
+                if require_divisible {
+                    return Err(Error::new_spanned(
+                        ident,
+                        format!(
+                            "`{}` ({}) isn't a multiple of `{}` ({})",
+                            ident, value, prev_ident, prev
+                        ),
+                    ));
+                }
+                divisible = false;
+                previous = Some((value, ident));
+            }
+            _ => previous = Some((value, ident)),
+        }
+        // Ord follows discriminants, so they have to agree with the values.
+        let disc = match (discriminant(variant)?, previous_discriminant) {
+            (Some(disc), _) => disc,
+            (None, Some((prev, _))) => prev + 1,
+            (None, None) => 0,
         };
+        match previous_discriminant {
+            Some((prev, prev_ident)) if prev >= disc => {
+                return Err(Error::new_spanned(
+                    ident,
+                    format!(
+                        "`{}` (= {}) would sort before `{}` (= {}), discriminants must follow the unit values",
+                        ident, disc, prev_ident, prev
+                    ),
+                ))
+            }
+            _ => previous_discriminant = Some((disc, ident)),
+        }
+        let unit_name = attrs.name.unwrap_or_else(|| ident.to_string());
+        let short = attrs.short.unwrap_or_else(|| unit_name.clone());
+        let plural = attrs.plural.unwrap_or_else(|| format!("{}s", unit_name));
 
-        values.push(quote! { #name::#ident #params });
+        unit_values.push(quote! { #unit => #value });
+        names.push(quote! { #unit => #unit_name });
+        shorts.push(quote! { #unit => #short });
+        plurals.push(quote! { #unit => #plural });
+        values.push(unit);
     }
     let variant_count = values.len();
-    let gen = quote! {
+    Ok(quote! {
         impl Unit for #name {
 
             fn variants() -> &'static [#name]{
                 const UNITS: [#name; #variant_count]  = [#(#values),*];
                 &UNITS
             }
+
+            fn divisible() -> bool {
+                #divisible
+            }
+
+            fn value(&self) -> i64 {
+                match *self {
+                    #(#unit_values),*
+                }
+            }
+
+            fn name(&self) -> &'static str {
+                match *self {
+                    #(#names),*
+                }
+            }
+
+            fn short(&self) -> &'static str {
+                match *self {
+                    #(#shorts),*
+                }
+            }
+
+            fn plural(&self) -> &'static str {
+                match *self {
+                    #(#plurals),*
+                }
+            }
         }
-    };
-    gen.into()
+    })
 }
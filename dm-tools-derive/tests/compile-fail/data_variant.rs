@@ -0,0 +1,10 @@
+use dm_tools_derive::Unit;
+
+#[derive(Unit)]
+enum Coin {
+    #[unit(value = 1)]
+    Copper,
+    Silver(i64),
+}
+
+fn main() {}
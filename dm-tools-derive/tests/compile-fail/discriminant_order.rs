@@ -0,0 +1,11 @@
+use dm_tools_derive::Unit;
+
+#[derive(Unit)]
+enum Distance {
+    #[unit(value = 5)]
+    Step = 10,
+    #[unit(value = 7)]
+    Stride = 3,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use dm_tools_derive::Unit;
+
+#[derive(Unit)]
+enum Coin {
+    Copper = 1,
+    #[unit(value = 1)]
+    Penny,
+}
+
+fn main() {}
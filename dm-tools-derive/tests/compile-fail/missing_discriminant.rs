@@ -0,0 +1,9 @@
+use dm_tools_derive::Unit;
+
+#[derive(Unit)]
+enum Coin {
+    Copper = 1,
+    Silver,
+}
+
+fn main() {}
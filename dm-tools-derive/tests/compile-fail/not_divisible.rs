@@ -0,0 +1,12 @@
+use dm_tools_derive::Unit;
+
+#[derive(Unit)]
+#[unit(divisible)]
+enum Coin {
+    Penny = 1,
+    Nickel = 5,
+    Dime = 10,
+    Quarter = 25,
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use dm_tools_derive::Unit;
+
+#[derive(Unit)]
+struct Coin {
+    value: i64,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use dm_tools_derive::Unit;
+
+#[derive(Unit)]
+enum Coin {
+    #[unit(symbol = "c")]
+    Copper = 1,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use dm_tools_derive::Unit;
+
+#[derive(Unit)]
+enum Coin {
+    Copper = 1,
+    Gold = 100,
+    Silver = 10,
+}
+
+fn main() {}
@@ -0,0 +1,61 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use dm_tools_derive::Unit;
+use num_derive::{FromPrimitive, ToPrimitive};
+use strum_macros::AsStaticStr;
+
+use dm_tools::coin::Coin;
+use dm_tools::time::UnitTime;
+use dm_tools::unit::{Unit, UnitCounter};
+
+/// Old coins valued in farthings, where crowns don't divide guineas.
+#[derive(
+    Debug,
+    Copy,
+    Clone,
+    Hash,
+    Eq,
+    PartialEq,
+    Unit,
+    FromPrimitive,
+    ToPrimitive,
+    Ord,
+    PartialOrd,
+    AsStaticStr,
+)]
+enum Sterling {
+    Farthing = 1,
+    Penny = 4,
+    Shilling = 48,
+    Crown = 240,
+    Guinea = 1008,
+}
+
+fn distribute(c: &mut Criterion) {
+    let year = UnitTime::Year.value() as usize;
+    c.bench_function("distribute a year of seconds", |b| {
+        b.iter(|| UnitTime::distribute(black_box(year)))
+    });
+    c.bench_function("distribute a century of seconds", |b| {
+        b.iter(|| UnitTime::distribute(black_box(year * 100 + 59)))
+    });
+    c.bench_function("distribute a dragon hoard", |b| {
+        b.iter(|| Coin::distribute(black_box(12_345_678)))
+    });
+    c.bench_function("distribute into units that don't divide", |b| {
+        b.iter(|| Sterling::distribute(black_box(year + 7)))
+    });
+}
+
+fn counter(c: &mut Criterion) {
+    let year = UnitTime::Year.value();
+    c.bench_function("advance a counter by a year", |b| {
+        b.iter(|| {
+            let mut counter = UnitCounter::<UnitTime>::new();
+            counter.add(black_box(year)).unwrap();
+            counter.redistribute().unwrap();
+        })
+    });
+}
+
+criterion_group!(benches, distribute, counter);
+criterion_main!(benches);
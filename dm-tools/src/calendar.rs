@@ -0,0 +1,154 @@
+use serde::de::Error as _;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config;
+use crate::table::LoadError;
+use crate::time::{Event, Time, UnitTime};
+use crate::unit::Unit;
+
+/// Days in a month, as the clock counts them.
+pub const DAYS_IN_MONTH: i64 = UnitTime::Month as i64 / UnitTime::Day as i64;
+
+/// Days in a week.
+pub const DAYS_IN_WEEK: i64 = UnitTime::Week as i64 / UnitTime::Day as i64;
+
+/// Months in a year.
+pub const MONTHS_IN_YEAR: i64 = UnitTime::Year as i64 / UnitTime::Month as i64;
+
+/// A day on the campaign calendar. Months and days count from 0, like years.
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Date {
+    pub year: i64,
+    pub month: i64,
+    pub day: i64,
+}
+
+impl Date {
+    /// The day `time` falls on.
+    pub fn from_time(time: &Time) -> Self {
+        Self::from_index(time.value.div_euclid(UnitTime::Day.value()))
+    }
+
+    /// The day `index` days after the first.
+    pub fn from_index(index: i64) -> Self {
+        let months = index.div_euclid(DAYS_IN_MONTH);
+        Self {
+            year: months.div_euclid(MONTHS_IN_YEAR),
+            month: months.rem_euclid(MONTHS_IN_YEAR),
+            day: index.rem_euclid(DAYS_IN_MONTH),
+        }
+    }
+
+    /// Days since the first day.
+    pub fn index(&self) -> i64 {
+        (self.year * MONTHS_IN_YEAR + self.month) * DAYS_IN_MONTH + self.day
+    }
+
+    /// Midnight at the start of the day.
+    pub fn start(&self) -> Time {
+        Time::from(self.index(), UnitTime::Day)
+    }
+
+    /// Day of the week, from 0.
+    pub fn weekday(&self) -> i64 {
+        self.index().rem_euclid(DAYS_IN_WEEK)
+    }
+
+    /// First day of the month.
+    pub fn first_of_month(&self) -> Self {
+        Self { day: 0, ..*self }
+    }
+
+    /// Same day `months` months later, or earlier when negative.
+    pub fn add_months(&self, months: i64) -> Self {
+        let months = self.year * MONTHS_IN_YEAR + self.month + months;
+        Self {
+            year: months.div_euclid(MONTHS_IN_YEAR),
+            month: months.rem_euclid(MONTHS_IN_YEAR),
+            day: self.day,
+        }
+    }
+
+    /// Every day `event` is active on, in order.
+    pub fn days_of(event: &Event) -> impl Iterator<Item = Date> {
+        let first = Date::from_time(&event.start).index();
+        let last = Date::from_time(&event.end).index();
+        (first..=last).map(Date::from_index)
+    }
+}
+
+/// Names for the campaign's months and weekdays, as loaded from
+/// `config_path`. Their lengths follow `UnitTime`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Calendar {
+    pub months: Vec<String>,
+    pub weekdays: Vec<String>,
+}
+
+impl Default for Calendar {
+    /// The calendar shipped in `data/calendar/default.json`.
+    fn default() -> Self {
+        Self::from_json(include_str!("../data/calendar/default.json")).expect("Default calendar")
+    }
+}
+
+impl Calendar {
+    pub fn from_json(json: &str) -> Result<Self, LoadError> {
+        let calendar: Self = serde_json::from_str(json)?;
+        if calendar.months.len() as i64 != MONTHS_IN_YEAR {
+            return Err(serde_json::Error::custom(format!(
+                "expected {} months, found {}",
+                MONTHS_IN_YEAR,
+                calendar.months.len()
+            ))
+            .into());
+        }
+        if calendar.weekdays.len() as i64 != DAYS_IN_WEEK {
+            return Err(serde_json::Error::custom(format!(
+                "expected {} weekdays, found {}",
+                DAYS_IN_WEEK,
+                calendar.weekdays.len()
+            ))
+            .into());
+        }
+        Ok(calendar)
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, LoadError> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+
+    /// The user's calendar, or the default without a config file.
+    pub fn load() -> Result<Self, LoadError> {
+        match config_path() {
+            Some(path) if path.exists() => Self::from_file(path),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    pub fn month_name(&self, date: &Date) -> &str {
+        &self.months[date.month as usize]
+    }
+
+    pub fn weekday_name(&self, date: &Date) -> &str {
+        &self.weekdays[date.weekday() as usize]
+    }
+
+    /// e.g. "Windsday 3 Thawmoon, year 0".
+    pub fn describe(&self, date: &Date) -> String {
+        format!(
+            "{} {} {}, year {}",
+            self.weekday_name(date),
+            date.day + 1,
+            self.month_name(date),
+            date.year
+        )
+    }
+}
+
+/// Where the user's calendar lives, e.g. `~/.config/dm-toolkit/calendar.json`.
+pub fn config_path() -> Option<PathBuf> {
+    config::path("calendar.json")
+}
@@ -0,0 +1,382 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use strum_macros::AsStaticStr;
+
+use crate::dice::Dice;
+use crate::inventory::{Encumbrance, EncumbranceRule, Inventory, CAPACITY_PER_STRENGTH};
+use crate::level::{level_for_xp, slot_name, ClassProgression, HitPoints, LevelUp};
+use crate::resource::{Recovery, Resource, Resources, Rest};
+
+#[derive(
+    Debug, Copy, Clone, Hash, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize, AsStaticStr,
+)]
+pub enum Ability {
+    Strength,
+    Dexterity,
+    Constitution,
+    Intelligence,
+    Wisdom,
+    Charisma,
+}
+
+impl Ability {
+    pub fn variants() -> &'static [Ability] {
+        use Ability::*;
+        &[
+            Strength,
+            Dexterity,
+            Constitution,
+            Intelligence,
+            Wisdom,
+            Charisma,
+        ]
+    }
+
+    pub fn short(&self) -> &'static str {
+        match self {
+            Ability::Strength => "STR",
+            Ability::Dexterity => "DEX",
+            Ability::Constitution => "CON",
+            Ability::Intelligence => "INT",
+            Ability::Wisdom => "WIS",
+            Ability::Charisma => "CHA",
+        }
+    }
+}
+
+#[derive(
+    Debug, Copy, Clone, Hash, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize, AsStaticStr,
+)]
+pub enum Skill {
+    Acrobatics,
+    AnimalHandling,
+    Arcana,
+    Athletics,
+    Deception,
+    History,
+    Insight,
+    Intimidation,
+    Investigation,
+    Medicine,
+    Nature,
+    Perception,
+    Performance,
+    Persuasion,
+    Religion,
+    SleightOfHand,
+    Stealth,
+    Survival,
+}
+
+impl Skill {
+    pub fn variants() -> &'static [Skill] {
+        use Skill::*;
+        &[
+            Acrobatics,
+            AnimalHandling,
+            Arcana,
+            Athletics,
+            Deception,
+            History,
+            Insight,
+            Intimidation,
+            Investigation,
+            Medicine,
+            Nature,
+            Perception,
+            Performance,
+            Persuasion,
+            Religion,
+            SleightOfHand,
+            Stealth,
+            Survival,
+        ]
+    }
+
+    pub fn ability(&self) -> Ability {
+        use Skill::*;
+        match self {
+            Athletics => Ability::Strength,
+            Acrobatics | SleightOfHand | Stealth => Ability::Dexterity,
+            Arcana | History | Investigation | Nature | Religion => Ability::Intelligence,
+            AnimalHandling | Insight | Medicine | Perception | Survival => Ability::Wisdom,
+            Deception | Intimidation | Performance | Persuasion => Ability::Charisma,
+        }
+    }
+}
+
+/// Levels of exhaustion before dying.
+pub const MAX_EXHAUSTION: u8 = 6;
+
+/// Modifier for an ability score, rounded down.
+pub fn modifier(score: i64) -> i64 {
+    (score - 10).div_euclid(2)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AbilityScores {
+    pub strength: i64,
+    pub dexterity: i64,
+    pub constitution: i64,
+    pub intelligence: i64,
+    pub wisdom: i64,
+    pub charisma: i64,
+}
+
+impl AbilityScores {
+    pub fn get(&self, ability: Ability) -> i64 {
+        match ability {
+            Ability::Strength => self.strength,
+            Ability::Dexterity => self.dexterity,
+            Ability::Constitution => self.constitution,
+            Ability::Intelligence => self.intelligence,
+            Ability::Wisdom => self.wisdom,
+            Ability::Charisma => self.charisma,
+        }
+    }
+
+    pub fn set(&mut self, ability: Ability, score: i64) {
+        let field = match ability {
+            Ability::Strength => &mut self.strength,
+            Ability::Dexterity => &mut self.dexterity,
+            Ability::Constitution => &mut self.constitution,
+            Ability::Intelligence => &mut self.intelligence,
+            Ability::Wisdom => &mut self.wisdom,
+            Ability::Charisma => &mut self.charisma,
+        };
+        *field = score;
+    }
+}
+
+impl Default for AbilityScores {
+    fn default() -> Self {
+        Self {
+            strength: 10,
+            dexterity: 10,
+            constitution: 10,
+            intelligence: 10,
+            wisdom: 10,
+            charisma: 10,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Character {
+    pub name: String,
+    pub class: String,
+    pub level: u8,
+    pub abilities: AbilityScores,
+    pub saves: BTreeSet<Ability>,
+    pub skills: BTreeSet<Skill>,
+    pub hp: i64,
+    pub max_hp: i64,
+    pub ac: i64,
+    /// Sides of the class hit die, e.g. 10 for a d10.
+    #[serde(default = "default_hit_die")]
+    pub hit_die: u32,
+    /// Hit dice left to spend, up to one per level.
+    #[serde(default)]
+    pub hit_dice: u32,
+    #[serde(default)]
+    pub exhaustion: u8,
+    #[serde(default)]
+    pub xp: i64,
+    /// Milestones reached, each worth a level.
+    #[serde(default)]
+    pub milestones: Vec<String>,
+    /// Levels earned from milestones but not yet taken.
+    #[serde(default)]
+    pub milestone_levels: u8,
+    #[serde(default)]
+    pub inventory: Inventory,
+    #[serde(default)]
+    pub resources: Resources,
+}
+
+impl Character {
+    pub fn new(name: &str, class: &str, level: u8) -> Self {
+        Self {
+            name: name.to_string(),
+            class: class.to_string(),
+            level: level.clamp(1, 20),
+            abilities: AbilityScores::default(),
+            saves: BTreeSet::new(),
+            skills: BTreeSet::new(),
+            hp: 0,
+            max_hp: 0,
+            ac: 10,
+            hit_die: default_hit_die(),
+            hit_dice: u32::from(level.clamp(1, 20)),
+            exhaustion: 0,
+            xp: 0,
+            milestones: Vec::new(),
+            milestone_levels: 0,
+            inventory: Inventory::new(),
+            resources: Resources::new(),
+        }
+    }
+
+    pub fn proficiency_bonus(&self) -> i64 {
+        2 + (i64::from(self.level.max(1)) - 1) / 4
+    }
+
+    pub fn modifier(&self, ability: Ability) -> i64 {
+        modifier(self.abilities.get(ability))
+    }
+
+    pub fn save(&self, ability: Ability) -> i64 {
+        if self.saves.contains(&ability) {
+            self.modifier(ability) + self.proficiency_bonus()
+        } else {
+            self.modifier(ability)
+        }
+    }
+
+    pub fn skill(&self, skill: Skill) -> i64 {
+        if self.skills.contains(&skill) {
+            self.modifier(skill.ability()) + self.proficiency_bonus()
+        } else {
+            self.modifier(skill.ability())
+        }
+    }
+
+    pub fn passive_perception(&self) -> i64 {
+        10 + self.skill(Skill::Perception)
+    }
+
+    pub fn initiative(&self) -> i64 {
+        self.modifier(Ability::Dexterity)
+    }
+
+    /// Pounds that can be carried, see `inventory::encumbrance`.
+    pub fn carrying_capacity(&self) -> f64 {
+        self.abilities.strength.max(0) as f64 * CAPACITY_PER_STRENGTH
+    }
+
+    pub fn encumbrance(&self, rule: EncumbranceRule) -> Encumbrance {
+        self.inventory.encumbrance(self.abilities.strength, rule)
+    }
+
+    pub fn heal(&mut self, amount: i64) {
+        self.hp = (self.hp + amount.max(0)).min(self.max_hp);
+    }
+
+    /// Spend a hit die, healing by the roll plus the Constitution modifier.
+    /// Returns the amount rolled, or `None` with no hit dice left.
+    pub fn spend_hit_die<R: Rng>(&mut self, rng: &mut R) -> Option<i64> {
+        if self.hit_dice == 0 {
+            return None;
+        }
+        self.hit_dice -= 1;
+        let dice = Dice::new(1, self.hit_die, self.modifier(Ability::Constitution));
+        let roll = dice.roll(rng).max(0);
+        self.heal(roll);
+        Some(roll)
+    }
+
+    pub fn add_exhaustion(&mut self, levels: i8) {
+        let exhaustion = i16::from(self.exhaustion) + i16::from(levels);
+        self.exhaustion = exhaustion.clamp(0, i16::from(MAX_EXHAUSTION)) as u8;
+    }
+
+    /// Spend up to `hit_dice` hit dice, returning each roll.
+    pub fn short_rest<R: Rng>(&mut self, hit_dice: u32, rng: &mut R) -> Vec<i64> {
+        self.resources.rest(Rest::Short);
+        (0..hit_dice)
+            .map_while(|_| self.spend_hit_die(rng))
+            .collect()
+    }
+
+    /// Regain all hit points, half of the hit dice and a level of exhaustion.
+    pub fn long_rest(&mut self) {
+        self.resources.rest(Rest::Long);
+        self.hp = self.max_hp;
+        let level = u32::from(self.level);
+        self.hit_dice = (self.hit_dice + (level / 2).max(1)).min(level);
+        self.add_exhaustion(-1);
+    }
+
+    pub fn award_xp(&mut self, xp: i64) {
+        self.xp = (self.xp + xp).max(0);
+    }
+
+    pub fn award_milestone(&mut self, name: &str) {
+        self.milestones.push(name.to_string());
+        self.milestone_levels = self.milestone_levels.saturating_add(1);
+    }
+
+    /// Levels earned through experience or milestones but not yet taken.
+    pub fn pending_levels(&self) -> u8 {
+        let from_xp = level_for_xp(self.xp).saturating_sub(self.level);
+        from_xp
+            .saturating_add(self.milestone_levels)
+            .min(20u8.saturating_sub(self.level))
+    }
+
+    /// Take a pending level, raising hit points and, given the class
+    /// progression, spell slots. Returns `None` if no level is pending.
+    pub fn level_up<R: Rng>(
+        &mut self,
+        hp: HitPoints,
+        progression: Option<&ClassProgression>,
+        rng: &mut R,
+    ) -> Option<LevelUp> {
+        if self.pending_levels() == 0 {
+            return None;
+        }
+        if level_for_xp(self.xp) <= self.level {
+            self.milestone_levels -= 1;
+        }
+        self.level += 1;
+
+        let constitution = self.modifier(Ability::Constitution);
+        let hit_die = progression.map_or(self.hit_die, |p| p.hit_die);
+        let gained = match hp {
+            HitPoints::Rolled => Dice::new(1, hit_die, constitution).roll(rng),
+            HitPoints::Average => Dice::new(1, hit_die, constitution).average() + 1,
+        }
+        .max(1);
+        self.max_hp += gained;
+        self.hp += gained;
+        self.hit_dice += 1;
+
+        let slots = progression.map_or_else(Vec::new, |p| self.update_slots(p));
+        Some(LevelUp {
+            level: self.level,
+            hp: gained,
+            proficiency_bonus: self.proficiency_bonus(),
+            slots,
+        })
+    }
+
+    /// Match spell slot resources to the class progression at the current
+    /// level, returning the slots gained since the previous level.
+    fn update_slots(&mut self, progression: &ClassProgression) -> Vec<(u8, u32)> {
+        let previous = progression.slots(self.level - 1).to_vec();
+        let mut gained = Vec::new();
+        for (i, count) in progression.slots(self.level).iter().enumerate() {
+            let slot_level = i as u8 + 1;
+            let new = count.saturating_sub(previous.get(i).copied().unwrap_or(0));
+            let name = slot_name(slot_level);
+            match self.resources.get_mut(&name) {
+                Some(resource) => {
+                    resource.max = *count;
+                    resource.current = (resource.current + new).min(*count);
+                }
+                None => self
+                    .resources
+                    .push(Resource::new(&name, *count, Recovery::LongRest)),
+            }
+            if new > 0 {
+                gained.push((slot_level, new));
+            }
+        }
+        gained
+    }
+}
+
+fn default_hit_die() -> u32 {
+    8
+}
@@ -0,0 +1,225 @@
+use dm_tools_derive::Unit;
+use num_derive::{FromPrimitive, ToPrimitive};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+use strum::AsStaticRef;
+use strum_macros::AsStaticStr;
+
+use crate::unit::{CountError, Unit, UnitCounter};
+
+/// Standard coins, valued in copper pieces.
+#[derive(
+    Debug,
+    Copy,
+    Clone,
+    Hash,
+    Eq,
+    PartialEq,
+    Unit,
+    FromPrimitive,
+    ToPrimitive,
+    Ord,
+    PartialOrd,
+    AsStaticStr,
+    Serialize,
+    Deserialize,
+)]
+#[unit(divisible)]
+pub enum Coin {
+    #[unit(name = "Copper Piece", short = "cp")]
+    Copper = 1,
+    #[unit(name = "Silver Piece", short = "sp")]
+    Silver = 10,
+    #[unit(name = "Electrum Piece", short = "ep")]
+    Electrum = 50,
+    #[unit(name = "Gold Piece", short = "gp")]
+    Gold = 100,
+    #[unit(name = "Platinum Piece", short = "pp")]
+    Platinum = 1000,
+}
+
+impl FromStr for Coin {
+    type Err = CountError;
+
+    /// Parse the short ("gp"), full ("gold") or display ("gold pieces") name of a coin.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        Coin::variants()
+            .iter()
+            .find(|coin| {
+                [coin.short(), coin.as_static(), coin.name(), coin.plural()]
+                    .iter()
+                    .any(|name| s.eq_ignore_ascii_case(name))
+            })
+            .copied()
+            .ok_or_else(|| CountError::UnitNotFound(s.to_string()))
+    }
+}
+
+/// Parse coins such as "7 gp 5 sp" into a count for each coin.
+pub fn parse_coins(text: &str) -> Result<HashMap<Coin, i64>, CountError> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    if words.is_empty() {
+        return Err(CountError::invalid(text, "no coins given"));
+    }
+    if !words.len().is_multiple_of(2) {
+        return Err(CountError::invalid(
+            text,
+            "expected a count before each coin",
+        ));
+    }
+    let mut coins = HashMap::new();
+    for pair in words.chunks(2) {
+        let count = pair[0]
+            .parse::<i64>()
+            .map_err(|e| CountError::invalid(pair[0], e))?;
+        if count < 0 {
+            return Err(CountError::invalid(pair[0], "counts can't be negative"));
+        }
+        let coin = pair[1].parse::<Coin>()?;
+        let total = coins.entry(coin).or_insert(0i64);
+        *total = total.saturating_add(count);
+    }
+    Ok(coins)
+}
+
+/// Parse an amount such as "7 gp 5 sp" into its value in copper.
+pub fn parse_amount(text: &str) -> Result<i64, CountError> {
+    let coins = parse_coins(text)?;
+    Ok(coins
+        .iter()
+        .map(|(coin, count)| coin.value_from_count(*count))
+        .fold(0, i64::saturating_add))
+}
+
+/// Format coin counts largest first, e.g. "7 gp 5 sp".
+pub fn format_coins(coins: &HashMap<Coin, i64>) -> String {
+    let text: Vec<String> = Coin::variants()
+        .iter()
+        .rev()
+        .filter_map(|coin| match coins.get(coin) {
+            Some(&count) if count != 0 => Some(format!("{} {}", count, coin.short())),
+            _ => None,
+        })
+        .collect();
+    if text.is_empty() {
+        "0 cp".to_string()
+    } else {
+        text.join(" ")
+    }
+}
+
+/// Coins carried by a character or party. Coins are never converted on their
+/// own; they only change hands when paying or receiving change.
+pub struct Purse {
+    coins: UnitCounter<Coin>,
+}
+
+impl Default for Purse {
+    fn default() -> Self {
+        Self {
+            coins: UnitCounter::new(),
+        }
+    }
+}
+
+impl Purse {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn coins(&self) -> &UnitCounter<Coin> {
+        &self.coins
+    }
+
+    /// Count of every coin, for formatting or splitting.
+    pub fn counts(&self) -> HashMap<Coin, i64> {
+        Coin::variants()
+            .iter()
+            .map(|coin| (*coin, self.get_count(*coin)))
+            .collect()
+    }
+
+    pub fn get_count(&self, coin: Coin) -> i64 {
+        self.coins.get_count(&coin).expect("Every coin is counted")
+    }
+
+    /// Set the number of `coin` held, failing if that would go below zero.
+    pub fn set_coins(&mut self, count: i64, coin: Coin) -> Result<(), CountError> {
+        let held = self.get_count(coin);
+        if count < 0 {
+            return Err(CountError::NotEnough {
+                unit: coin.short(),
+                requested: held.saturating_sub(count),
+                available: held,
+            });
+        }
+        self.coins.set_units(count, &coin)
+    }
+
+    /// Set the number of `coin` held from text, see `UnitCounter::set_from_text`.
+    /// Fails without changing the purse if that would go below zero.
+    pub fn set_from_text(&mut self, text: &str, coin: Coin) -> Result<(), CountError> {
+        let held = self.get_count(coin);
+        self.coins.set_from_text(text, &coin)?;
+        let count = self.get_count(coin);
+        // Put it back, so it's only set if it doesn't go below zero.
+        self.coins.set_units(held, &coin)?;
+        self.set_coins(count, coin)
+    }
+
+    /// Add `count` of `coin`, failing if that would go below zero.
+    pub fn add_coins(&mut self, count: i64, coin: Coin) -> Result<(), CountError> {
+        self.set_coins(self.get_count(coin).saturating_add(count), coin)
+    }
+
+    /// Total value of the purse in copper.
+    pub fn total(&self) -> i64 {
+        self.coins.total()
+    }
+
+    /// Pay `cost` copper worth of coins, returning the change received.
+    ///
+    /// Coins on hand are handed over largest first. If that can't cover the
+    /// cost exactly, the smallest coin that covers the rest is handed over and
+    /// the change is returned in as few coins as possible. The purse is left
+    /// untouched if there isn't enough.
+    pub fn pay(&mut self, cost: i64) -> Result<HashMap<Coin, i64>, CountError> {
+        if cost < 0 {
+            return Err(CountError::invalid(
+                &cost.to_string(),
+                "costs can't be negative",
+            ));
+        }
+        if cost > self.total() {
+            return Err(CountError::NotEnough {
+                unit: Coin::Copper.short(),
+                requested: cost,
+                available: self.total(),
+            });
+        }
+
+        let mut rem = cost;
+        for coin in Coin::variants().iter().rev() {
+            let used = self.get_count(*coin).min(rem / coin.value());
+            self.add_coins(-used, *coin)?;
+            rem -= coin.value_from_count(used);
+        }
+        if rem == 0 {
+            return Ok(HashMap::new());
+        }
+
+        // Every coin left is worth more than `rem`, so break the smallest.
+        let coin = *Coin::variants()
+            .iter()
+            .find(|coin| self.get_count(**coin) > 0)
+            .expect("Coins to cover the cost");
+        self.add_coins(-1, coin)?;
+        let change = Coin::distribute((coin.value() - rem) as usize);
+        for (coin, count) in change.iter() {
+            self.add_coins(*count, *coin)?;
+        }
+        Ok(change)
+    }
+}
@@ -0,0 +1,675 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use strum::AsStaticRef;
+use strum_macros::AsStaticStr;
+
+use crate::dice::Dice;
+use crate::time::{Time, ROUND};
+
+/// Lair actions happen on this initiative count, losing ties.
+pub const LAIR_INITIATIVE: i64 = 20;
+
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq, Serialize, Deserialize, AsStaticStr)]
+pub enum Condition {
+    Blinded,
+    Charmed,
+    Deafened,
+    Frightened,
+    Grappled,
+    Incapacitated,
+    Invisible,
+    Paralyzed,
+    Petrified,
+    Poisoned,
+    Prone,
+    Restrained,
+    Stunned,
+    Unconscious,
+}
+
+impl Condition {
+    pub fn variants() -> &'static [Condition] {
+        use Condition::*;
+        &[
+            Blinded,
+            Charmed,
+            Deafened,
+            Frightened,
+            Grappled,
+            Incapacitated,
+            Invisible,
+            Paralyzed,
+            Petrified,
+            Poisoned,
+            Prone,
+            Restrained,
+            Stunned,
+            Unconscious,
+        ]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActiveCondition {
+    pub condition: Condition,
+    /// Round at the start of whose turn the condition ends, if it ends on its own.
+    pub until: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Combatant {
+    /// Unique within its combat, set when added, since names can repeat.
+    pub id: usize,
+    pub name: String,
+    pub initiative: i64,
+    pub hp: i64,
+    pub max_hp: i64,
+    pub conditions: Vec<ActiveCondition>,
+    /// Legendary actions per round, refreshed at the start of its turn.
+    pub legendary_actions: u32,
+    pub legendary_remaining: u32,
+    /// Whether this creature takes lair actions.
+    pub lair: bool,
+    /// Identical creatures share a group, and a turn under group initiative.
+    pub group: Option<String>,
+}
+
+impl Combatant {
+    pub fn new(name: &str, initiative: i64, hp: i64) -> Self {
+        Self {
+            id: 0,
+            name: name.to_string(),
+            initiative,
+            hp,
+            max_hp: hp,
+            conditions: Vec::new(),
+            legendary_actions: 0,
+            legendary_remaining: 0,
+            lair: false,
+            group: None,
+        }
+    }
+
+    pub fn with_group(mut self, group: &str) -> Self {
+        self.group = Some(group.to_string());
+        self
+    }
+
+    pub fn with_legendary_actions(mut self, actions: u32) -> Self {
+        self.legendary_actions = actions;
+        self.legendary_remaining = actions;
+        self
+    }
+
+    pub fn with_lair(mut self) -> Self {
+        self.lair = true;
+        self
+    }
+
+    /// Whether the creature can still act, e.g. to take a legendary action.
+    pub fn can_act(&self) -> bool {
+        self.hp > 0
+            && ![
+                Condition::Incapacitated,
+                Condition::Paralyzed,
+                Condition::Petrified,
+                Condition::Stunned,
+                Condition::Unconscious,
+            ]
+            .iter()
+            .any(|c| self.has_condition(*c))
+    }
+
+    pub fn has_condition(&self, condition: Condition) -> bool {
+        self.conditions.iter().any(|c| c.condition == condition)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Action {
+    Start,
+    End,
+    Turn {
+        name: String,
+    },
+    Lair {
+        name: String,
+    },
+    Legendary {
+        name: String,
+        cost: u32,
+        remaining: u32,
+    },
+    Roll {
+        name: String,
+        purpose: String,
+        dice: Dice,
+        result: i64,
+    },
+    Damage {
+        source: Option<String>,
+        target: String,
+        amount: i64,
+        hp: i64,
+    },
+    Heal {
+        target: String,
+        amount: i64,
+        hp: i64,
+    },
+    ConditionApplied {
+        target: String,
+        condition: Condition,
+    },
+    ConditionExpired {
+        target: String,
+        condition: Condition,
+    },
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Action::Start => write!(f, "Combat started"),
+            Action::End => write!(f, "Combat ended"),
+            Action::Turn { name } => write!(f, "{}'s turn", name),
+            Action::Lair { name } => write!(f, "{}'s lair action", name),
+            Action::Legendary {
+                name,
+                cost,
+                remaining,
+            } => write!(
+                f,
+                "{} took a legendary action costing {} ({} left)",
+                name, cost, remaining
+            ),
+            Action::Roll {
+                name,
+                purpose,
+                dice,
+                result,
+            } => write!(f, "{} rolled {} for {}: {}", name, dice, purpose, result),
+            Action::Damage {
+                source: Some(source),
+                target,
+                amount,
+                hp,
+            } => write!(
+                f,
+                "{} dealt {} damage to {} ({} HP)",
+                source, amount, target, hp
+            ),
+            Action::Damage {
+                source: None,
+                target,
+                amount,
+                hp,
+            } => write!(f, "{} took {} damage ({} HP)", target, amount, hp),
+            Action::Heal { target, amount, hp } => {
+                write!(f, "{} healed {} ({} HP)", target, amount, hp)
+            }
+            Action::ConditionApplied { target, condition } => {
+                write!(f, "{} is {}", target, condition.as_static())
+            }
+            Action::ConditionExpired { target, condition } => {
+                write!(f, "{} is no longer {}", target, condition.as_static())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogEntry {
+    pub round: u32,
+    pub time: Time,
+    pub action: Action,
+}
+
+/// Every action taken during a combat, in order.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct CombatLog {
+    entries: Vec<LogEntry>,
+}
+
+impl CombatLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, round: u32, time: Time, action: Action) {
+        self.entries.push(LogEntry {
+            round,
+            time,
+            action,
+        })
+    }
+
+    pub fn entries(&self) -> &Vec<LogEntry> {
+        &self.entries
+    }
+
+    /// Entries grouped under a heading per round, for session recaps.
+    pub fn to_markdown(&self) -> String {
+        let mut text = String::from("# Combat log\n");
+        let mut round = None;
+        for entry in self.entries.iter() {
+            if round != Some(entry.round) {
+                round = Some(entry.round);
+                text.push_str(&format!("\n## Round {}\n\n", entry.round));
+            }
+            text.push_str(&format!("- `{}` {}\n", entry.time, entry.action));
+        }
+        text
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+/// A slot in the turn order.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Turn {
+    Combatant(usize),
+    /// Shared turn of the group whose first member is at this index.
+    Group(usize),
+    /// Lair action of the combatant at this index.
+    Lair(usize),
+}
+
+impl Turn {
+    pub fn index(&self) -> usize {
+        match *self {
+            Turn::Combatant(i) | Turn::Group(i) | Turn::Lair(i) => i,
+        }
+    }
+}
+
+/// Who a turn belongs to, which holds while indices shift around it.
+enum Owner {
+    Combatant(usize),
+    Group(String),
+    Lair(usize),
+}
+
+/// Turn order and state of a single combat, recording everything in its log.
+pub struct Combat {
+    combatants: Vec<Combatant>,
+    next_id: usize,
+    round: u32,
+    turn: usize,
+    start: Time,
+    log: CombatLog,
+    group_initiative: bool,
+}
+
+impl Combat {
+    /// A combat beginning at world time `start`.
+    pub fn new(start: Time) -> Self {
+        Self {
+            combatants: Vec::new(),
+            next_id: 0,
+            round: 0,
+            turn: 0,
+            start,
+            log: CombatLog::new(),
+            group_initiative: false,
+        }
+    }
+
+    /// Let identical creatures share one slot in the turn order.
+    pub fn set_group_initiative(&mut self, enabled: bool) {
+        let owner = self.current_owner();
+        self.group_initiative = enabled;
+        self.keep_turn(owner);
+    }
+
+    pub fn group_initiative(&self) -> bool {
+        self.group_initiative
+    }
+
+    /// Add a combatant, keeping the order by initiative.
+    /// Ties go to whoever was added first.
+    pub fn add(&mut self, mut combatant: Combatant) {
+        combatant.id = self.next_id;
+        self.next_id += 1;
+        let index = self
+            .combatants
+            .iter()
+            .position(|c| c.initiative < combatant.initiative)
+            .unwrap_or(self.combatants.len());
+        let owner = self.current_owner();
+        self.combatants.insert(index, combatant);
+        // Keep the current turn when someone joins ahead of it.
+        self.keep_turn(owner);
+    }
+
+    fn current_owner(&self) -> Option<Owner> {
+        let owner = match self.current_turn()? {
+            Turn::Combatant(i) => Owner::Combatant(self.combatants[i].id),
+            Turn::Group(i) => Owner::Group(self.combatants[i].group.clone()?),
+            Turn::Lair(i) => Owner::Lair(self.combatants[i].id),
+        };
+        Some(owner)
+    }
+
+    /// Move back to `owner`'s turn after the order changed. A member's turn
+    /// becomes its group's when grouped, and a group's its first member's.
+    fn keep_turn(&mut self, owner: Option<Owner>) {
+        let owner = match owner {
+            Some(owner) => owner,
+            None => return,
+        };
+        let combatants = &self.combatants;
+        let owns = |turn: &Turn| match (&owner, *turn) {
+            (Owner::Lair(id), Turn::Lair(i)) => combatants[i].id == *id,
+            (Owner::Combatant(id), Turn::Combatant(i)) => combatants[i].id == *id,
+            (Owner::Combatant(id), Turn::Group(i)) => {
+                let group = combatants[i].group.as_deref();
+                combatants
+                    .iter()
+                    .any(|c| c.id == *id && c.group.as_deref() == group)
+            }
+            (Owner::Group(group), Turn::Group(i)) | (Owner::Group(group), Turn::Combatant(i)) => {
+                combatants[i].group.as_ref() == Some(group)
+            }
+            _ => false,
+        };
+        if let Some(turn) = self.turns().iter().position(owns) {
+            self.turn = turn;
+        }
+    }
+
+    /// Add `count` identical creatures named after their group, e.g. "Goblin 3".
+    pub fn add_group(&mut self, name: &str, count: usize, initiative: i64, hp: i64) {
+        for i in 1..=count {
+            let combatant = Combatant::new(&format!("{} {}", name, i), initiative, hp);
+            self.add(combatant.with_group(name));
+        }
+    }
+
+    /// Turn order for a round, with lair actions slotted in at their initiative.
+    pub fn turns(&self) -> Vec<Turn> {
+        let mut lairs = self
+            .combatants
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.lair)
+            .map(|(i, _)| Turn::Lair(i))
+            .peekable();
+        let mut groups: Vec<&str> = Vec::new();
+        let mut turns = Vec::new();
+        for (i, c) in self.combatants.iter().enumerate() {
+            if c.initiative < LAIR_INITIATIVE && lairs.peek().is_some() {
+                turns.extend(&mut lairs);
+            }
+            match c.group.as_ref() {
+                Some(group) if self.group_initiative => {
+                    if !groups.contains(&group.as_str()) {
+                        groups.push(group);
+                        turns.push(Turn::Group(i));
+                    }
+                }
+                _ => turns.push(Turn::Combatant(i)),
+            }
+        }
+        turns.extend(lairs);
+        turns
+    }
+
+    /// Combatants acting on `turn`.
+    pub fn members(&self, turn: Turn) -> Vec<usize> {
+        match turn {
+            Turn::Group(i) => self.group_members(self.combatants[i].group.as_ref().unwrap()),
+            Turn::Combatant(i) | Turn::Lair(i) => vec![i],
+        }
+    }
+
+    pub fn group_members(&self, group: &str) -> Vec<usize> {
+        self.combatants
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.group.as_deref() == Some(group))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    pub fn current_turn(&self) -> Option<Turn> {
+        if self.is_started() {
+            self.turns().get(self.turn).cloned()
+        } else {
+            None
+        }
+    }
+
+    pub fn combatants(&self) -> &Vec<Combatant> {
+        &self.combatants
+    }
+
+    /// Where the combatant with `id` is in `combatants()`.
+    pub fn position(&self, id: usize) -> Option<usize> {
+        self.combatants.iter().position(|c| c.id == id)
+    }
+
+    pub fn round(&self) -> u32 {
+        self.round
+    }
+
+    pub fn is_started(&self) -> bool {
+        self.round > 0
+    }
+
+    /// Combatant whose turn it is, or whose lair is acting.
+    pub fn current(&self) -> Option<&Combatant> {
+        self.current_index().and_then(|i| self.combatants.get(i))
+    }
+
+    pub fn current_index(&self) -> Option<usize> {
+        self.current_turn().map(|t| t.index())
+    }
+
+    /// Combatants that may take a legendary action now, at the end of another creature's turn.
+    pub fn legendary_available(&self) -> Vec<usize> {
+        let current = match self.current_turn() {
+            Some(Turn::Lair(_)) | None => vec![],
+            Some(turn) => self.members(turn),
+        };
+        self.combatants
+            .iter()
+            .enumerate()
+            .filter(|(i, c)| !current.contains(i) && c.legendary_remaining > 0 && c.can_act())
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Spend `cost` legendary actions of `target`, if it has enough left.
+    pub fn use_legendary(&mut self, target: usize, cost: u32) -> bool {
+        if !self.legendary_available().contains(&target) {
+            return false;
+        }
+        let combatant = &mut self.combatants[target];
+        if combatant.legendary_remaining < cost {
+            return false;
+        }
+        combatant.legendary_remaining -= cost;
+        let action = Action::Legendary {
+            name: combatant.name.clone(),
+            cost,
+            remaining: combatant.legendary_remaining,
+        };
+        self.record(action);
+        true
+    }
+
+    /// Move the start to world time `start`, e.g. when the clock moved
+    /// before the first round.
+    pub fn set_start(&mut self, start: Time) {
+        self.start = start;
+    }
+
+    /// Time spent fighting, counting every round started in full.
+    pub fn elapsed(&self) -> Time {
+        Time {
+            value: i64::from(self.round) * ROUND,
+        }
+    }
+
+    /// World time at the start of the current round.
+    pub fn time(&self) -> Time {
+        let rounds = i64::from(self.round.max(1) - 1);
+        Time {
+            value: self.start.value + rounds * ROUND,
+        }
+    }
+
+    pub fn log(&self) -> &CombatLog {
+        &self.log
+    }
+
+    fn record(&mut self, action: Action) {
+        let time = self.time();
+        self.log.push(self.round, time, action);
+    }
+
+    pub fn start(&mut self) {
+        self.round = 1;
+        self.turn = 0;
+        self.record(Action::Start);
+        self.begin_turn();
+    }
+
+    pub fn end(&mut self) {
+        self.record(Action::End);
+    }
+
+    /// Move to the next combatant, starting a new round after the last one.
+    pub fn next_turn(&mut self) {
+        if !self.is_started() {
+            return self.start();
+        }
+        self.turn += 1;
+        if self.turn >= self.turns().len() {
+            self.turn = 0;
+            self.round += 1;
+        }
+        self.begin_turn();
+    }
+
+    fn begin_turn(&mut self) {
+        let round = self.round;
+        let turn = match self.current_turn() {
+            Some(Turn::Lair(i)) => {
+                let name = self.combatants[i].name.clone();
+                return self.record(Action::Lair { name });
+            }
+            Some(turn) => turn,
+            None => return,
+        };
+        let name = match turn {
+            Turn::Group(i) => self.combatants[i].group.clone().unwrap_or_default(),
+            _ => self.combatants[turn.index()].name.clone(),
+        };
+        self.record(Action::Turn { name });
+
+        for index in self.members(turn).into_iter() {
+            let combatant = &mut self.combatants[index];
+            combatant.legendary_remaining = combatant.legendary_actions;
+            let (expired, remaining) = combatant
+                .conditions
+                .drain(..)
+                .partition(|c| c.until.is_some_and(|until| round >= until));
+            combatant.conditions = remaining;
+
+            let target = combatant.name.clone();
+            for c in expired.into_iter() {
+                self.record(Action::ConditionExpired {
+                    target: target.clone(),
+                    condition: c.condition,
+                });
+            }
+        }
+    }
+
+    /// Roll `dice` on behalf of `name` and record the result.
+    pub fn roll<R: Rng>(&mut self, name: &str, purpose: &str, dice: Dice, rng: &mut R) -> i64 {
+        let result = dice.roll(rng);
+        self.record_roll(name, purpose, dice, result);
+        result
+    }
+
+    /// Record a roll made at the table.
+    pub fn record_roll(&mut self, name: &str, purpose: &str, dice: Dice, result: i64) {
+        self.record(Action::Roll {
+            name: name.to_string(),
+            purpose: purpose.to_string(),
+            dice,
+            result,
+        });
+    }
+
+    pub fn damage(&mut self, target: usize, amount: i64, source: Option<&str>) {
+        if let Some(combatant) = self.combatants.get_mut(target) {
+            combatant.hp = (combatant.hp - amount).max(0);
+            let action = Action::Damage {
+                source: source.map(str::to_string),
+                target: combatant.name.clone(),
+                amount,
+                hp: combatant.hp,
+            };
+            self.record(action);
+        }
+    }
+
+    /// Damage every member of `group` still standing.
+    pub fn damage_group(&mut self, group: &str, amount: i64, source: Option<&str>) {
+        for target in self.group_members(group).into_iter() {
+            if self.combatants[target].hp > 0 {
+                self.damage(target, amount, source);
+            }
+        }
+    }
+
+    pub fn heal(&mut self, target: usize, amount: i64) {
+        if let Some(combatant) = self.combatants.get_mut(target) {
+            combatant.hp = (combatant.hp + amount).min(combatant.max_hp);
+            let action = Action::Heal {
+                target: combatant.name.clone(),
+                amount,
+                hp: combatant.hp,
+            };
+            self.record(action);
+        }
+    }
+
+    /// Apply `condition` to `target`, lasting `rounds` rounds if given.
+    pub fn apply_condition(&mut self, target: usize, condition: Condition, rounds: Option<u32>) {
+        let until = rounds.map(|r| self.round.max(1) + r);
+        if let Some(combatant) = self.combatants.get_mut(target) {
+            combatant.conditions.retain(|c| c.condition != condition);
+            combatant
+                .conditions
+                .push(ActiveCondition { condition, until });
+            let action = Action::ConditionApplied {
+                target: combatant.name.clone(),
+                condition,
+            };
+            self.record(action);
+        }
+    }
+
+    pub fn remove_condition(&mut self, target: usize, condition: Condition) {
+        if let Some(combatant) = self.combatants.get_mut(target) {
+            if combatant.has_condition(condition) {
+                combatant.conditions.retain(|c| c.condition != condition);
+                let action = Action::ConditionExpired {
+                    target: combatant.name.clone(),
+                    condition,
+                };
+                self.record(action);
+            }
+        }
+    }
+}
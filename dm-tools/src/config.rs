@@ -0,0 +1,10 @@
+use std::env;
+use std::path::{Path, PathBuf};
+
+/// Where the user's `file` lives, e.g. `~/.config/dm-toolkit/keys.json`.
+pub fn path(file: &str) -> Option<PathBuf> {
+    let config = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+    Some(config.join("dm-toolkit").join(file))
+}
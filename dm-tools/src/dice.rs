@@ -0,0 +1,118 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+/// A dice expression such as "1d4+1", "2d6" or a flat "3".
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Dice {
+    pub count: u32,
+    pub sides: u32,
+    pub modifier: i64,
+}
+
+impl Dice {
+    pub fn new(count: u32, sides: u32, modifier: i64) -> Self {
+        Self {
+            count,
+            sides,
+            modifier,
+        }
+    }
+
+    /// A fixed amount with no dice.
+    pub fn flat(modifier: i64) -> Self {
+        Self::new(0, 0, modifier)
+    }
+
+    pub fn roll<R: Rng>(&self, rng: &mut R) -> i64 {
+        let rolled: i64 = (0..self.count)
+            .map(|_| rng.gen_range(1, self.sides as i64 + 1))
+            .sum();
+        rolled + self.modifier
+    }
+
+    pub fn min(&self) -> i64 {
+        self.count as i64 + self.modifier
+    }
+
+    pub fn max(&self) -> i64 {
+        self.count as i64 * self.sides as i64 + self.modifier
+    }
+
+    /// Rounded down average, as used for fixed hit points and damage.
+    pub fn average(&self) -> i64 {
+        self.count as i64 * (self.sides as i64 + 1) / 2 + self.modifier
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidDice(pub String);
+
+impl fmt::Display for InvalidDice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid dice expression {:?}", self.0)
+    }
+}
+
+impl FromStr for Dice {
+    type Err = InvalidDice;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || InvalidDice(s.to_string());
+        let text: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+
+        // Split off a trailing modifier, keeping its sign.
+        let (dice, modifier) = match text.rfind(['+', '-']) {
+            Some(0) | None => (text.as_str(), "0"),
+            Some(i) => (&text[..i], &text[i..]),
+        };
+        let modifier = modifier.parse::<i64>().map_err(|_| err())?;
+
+        match dice.find(['d', 'D']) {
+            Some(i) => {
+                let count = match &dice[..i] {
+                    "" => 1,
+                    count => count.parse::<u32>().map_err(|_| err())?,
+                };
+                let sides = dice[i + 1..].parse::<u32>().map_err(|_| err())?;
+                if sides == 0 {
+                    return Err(err());
+                }
+                Ok(Self::new(count, sides, modifier))
+            }
+            None if modifier == 0 => dice.parse::<i64>().map(Self::flat).map_err(|_| err()),
+            None => Err(err()),
+        }
+    }
+}
+
+impl TryFrom<String> for Dice {
+    type Error = InvalidDice;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Dice> for String {
+    fn from(dice: Dice) -> Self {
+        dice.to_string()
+    }
+}
+
+impl fmt::Display for Dice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.count == 0 {
+            return write!(f, "{}", self.modifier);
+        }
+        write!(f, "{}d{}", self.count, self.sides)?;
+        match self.modifier {
+            0 => Ok(()),
+            m if m > 0 => write!(f, "+{}", m),
+            m => write!(f, "{}", m),
+        }
+    }
+}
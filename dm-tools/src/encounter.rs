@@ -0,0 +1,244 @@
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+use strum_macros::AsStaticStr;
+
+/// XP thresholds per character level, as `[Easy, Medium, Hard, Deadly]`.
+const XP_THRESHOLDS: [[i64; 4]; 20] = [
+    [25, 50, 75, 100],
+    [50, 100, 150, 200],
+    [75, 150, 225, 400],
+    [125, 250, 375, 500],
+    [250, 500, 750, 1100],
+    [300, 600, 900, 1400],
+    [350, 750, 1100, 1700],
+    [450, 900, 1400, 2100],
+    [550, 1100, 1600, 2400],
+    [600, 1200, 1900, 2800],
+    [800, 1600, 2400, 3600],
+    [1000, 2000, 3000, 4500],
+    [1100, 2200, 3400, 5100],
+    [1250, 2500, 3800, 5700],
+    [1400, 2800, 4300, 6400],
+    [1600, 3200, 4800, 7200],
+    [2000, 3900, 5900, 8800],
+    [2100, 4200, 6300, 9500],
+    [2400, 4900, 7300, 10900],
+    [2800, 5700, 8500, 12700],
+];
+
+/// XP by whole challenge rating, starting at CR 1.
+const CR_XP: [i64; 30] = [
+    200, 450, 700, 1100, 1800, 2300, 2900, 3900, 5000, 5900, 7200, 8400, 10000, 11500, 13000,
+    15000, 18000, 20000, 22000, 25000, 33000, 41000, 50000, 62000, 75000, 90000, 105000, 120000,
+    135000, 155000,
+];
+
+/// Encounter multipliers, in halves: x0.5, x1, x1.5, x2, x2.5, x3, x4, x5.
+const MULTIPLIERS: [i64; 8] = [1, 2, 3, 4, 5, 6, 8, 10];
+
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq, Ord, PartialOrd, AsStaticStr)]
+pub enum Difficulty {
+    Trivial,
+    Easy,
+    Medium,
+    Hard,
+    Deadly,
+}
+
+impl Difficulty {
+    /// XP a single character of `level` must face for an encounter to be this difficult.
+    pub fn threshold(&self, level: u8) -> i64 {
+        let level = level.clamp(1, 20) as usize;
+        let row = &XP_THRESHOLDS[level - 1];
+        match self {
+            Difficulty::Trivial => 0,
+            Difficulty::Easy => row[0],
+            Difficulty::Medium => row[1],
+            Difficulty::Hard => row[2],
+            Difficulty::Deadly => row[3],
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum ChallengeRating {
+    Zero,
+    Eighth,
+    Quarter,
+    Half,
+    Whole(u8),
+}
+
+impl ChallengeRating {
+    pub fn xp(&self) -> i64 {
+        match *self {
+            ChallengeRating::Zero => 10,
+            ChallengeRating::Eighth => 25,
+            ChallengeRating::Quarter => 50,
+            ChallengeRating::Half => 100,
+            ChallengeRating::Whole(cr) => CR_XP[cr.clamp(1, 30) as usize - 1],
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InvalidChallengeRating;
+
+impl fmt::Display for InvalidChallengeRating {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "challenge rating must be 0, 1/8, 1/4, 1/2 or 1 to 30")
+    }
+}
+
+impl FromStr for ChallengeRating {
+    type Err = InvalidChallengeRating;
+
+    /// Parse a challenge rating as written in a stat block, e.g. "1/4" or "5".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "0" => Ok(ChallengeRating::Zero),
+            "1/8" => Ok(ChallengeRating::Eighth),
+            "1/4" => Ok(ChallengeRating::Quarter),
+            "1/2" => Ok(ChallengeRating::Half),
+            other => match other.parse::<u8>() {
+                Ok(cr) if (1..=30).contains(&cr) => Ok(ChallengeRating::Whole(cr)),
+                _ => Err(InvalidChallengeRating),
+            },
+        }
+    }
+}
+
+impl TryFrom<String> for ChallengeRating {
+    type Error = InvalidChallengeRating;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<ChallengeRating> for String {
+    fn from(cr: ChallengeRating) -> Self {
+        cr.to_string()
+    }
+}
+
+impl fmt::Display for ChallengeRating {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ChallengeRating::Zero => write!(f, "0"),
+            ChallengeRating::Eighth => write!(f, "1/8"),
+            ChallengeRating::Quarter => write!(f, "1/4"),
+            ChallengeRating::Half => write!(f, "1/2"),
+            ChallengeRating::Whole(cr) => write!(f, "{}", cr),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Monster {
+    pub name: String,
+    pub cr: ChallengeRating,
+    pub count: usize,
+}
+
+impl Monster {
+    pub fn new(name: &str, cr: ChallengeRating, count: usize) -> Self {
+        Self {
+            name: name.to_string(),
+            cr,
+            count,
+        }
+    }
+
+    pub fn xp(&self) -> i64 {
+        self.cr.xp() * self.count as i64
+    }
+}
+
+/// A party of characters, by level, facing a group of monsters.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Encounter {
+    pub party: Vec<u8>,
+    pub monsters: Vec<Monster>,
+}
+
+impl Encounter {
+    pub fn new(party: Vec<u8>, monsters: Vec<Monster>) -> Self {
+        Self { party, monsters }
+    }
+
+    /// Combined threshold of the party for `difficulty`.
+    pub fn threshold(&self, difficulty: Difficulty) -> i64 {
+        self.party
+            .iter()
+            .map(|level| difficulty.threshold(*level))
+            .sum()
+    }
+
+    pub fn monster_count(&self) -> usize {
+        self.monsters.iter().map(|m| m.count).sum()
+    }
+
+    /// Total XP of all monsters, before any multiplier.
+    pub fn base_xp(&self) -> i64 {
+        self.monsters.iter().map(Monster::xp).sum()
+    }
+
+    /// Multiplier for the number of monsters, in halves.
+    ///
+    /// Parties of fewer than three characters use the next higher multiplier,
+    /// parties of six or more the next lower one.
+    pub fn multiplier_halves(&self) -> i64 {
+        let index = match self.monster_count() {
+            0 | 1 => 1,
+            2 => 2,
+            3..=6 => 3,
+            7..=10 => 4,
+            11..=14 => 5,
+            _ => 6,
+        };
+        let index = match self.party.len() {
+            0..=2 => index + 1,
+            3..=5 => index,
+            _ => index - 1,
+        };
+        MULTIPLIERS[index]
+    }
+
+    pub fn multiplier(&self) -> f64 {
+        self.multiplier_halves() as f64 / 2.0
+    }
+
+    /// Monster XP adjusted by the multiplier, used to judge difficulty.
+    pub fn adjusted_xp(&self) -> i64 {
+        self.base_xp() * self.multiplier_halves() / 2
+    }
+
+    pub fn difficulty(&self) -> Difficulty {
+        let xp = self.adjusted_xp();
+        [
+            Difficulty::Deadly,
+            Difficulty::Hard,
+            Difficulty::Medium,
+            Difficulty::Easy,
+        ]
+        .iter()
+        .cloned()
+        .find(|d| {
+            let threshold = self.threshold(*d);
+            threshold > 0 && xp >= threshold
+        })
+        .unwrap_or(Difficulty::Trivial)
+    }
+
+    /// XP awarded to each character for defeating the monsters.
+    pub fn xp_per_character(&self) -> i64 {
+        match self.party.len() {
+            0 => 0,
+            n => self.base_xp() / n as i64,
+        }
+    }
+}
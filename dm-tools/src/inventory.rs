@@ -0,0 +1,224 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use strum_macros::AsStaticStr;
+
+use crate::coin::Coin;
+use crate::unit::Unit;
+
+/// Pounds carried per point of Strength before going over capacity.
+pub const CAPACITY_PER_STRENGTH: f64 = 15.0;
+/// Variant rule thresholds, in pounds per point of Strength.
+pub const ENCUMBERED_PER_STRENGTH: f64 = 5.0;
+pub const HEAVILY_ENCUMBERED_PER_STRENGTH: f64 = 10.0;
+/// Walking speed over capacity, in feet, however fast the creature is.
+pub const OVER_CAPACITY_SPEED: i64 = 5;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Item {
+    pub name: String,
+    pub quantity: u32,
+    /// Weight of a single item, in pounds.
+    pub weight: f64,
+    /// Value of a single item.
+    pub value: HashMap<Coin, i64>,
+    pub contents: Vec<Item>,
+    /// Contents don't add to the weight, as with a Bag of Holding.
+    pub weightless_contents: bool,
+}
+
+impl Item {
+    pub fn new(name: &str, quantity: u32, weight: f64) -> Self {
+        Self {
+            name: name.to_string(),
+            quantity,
+            weight,
+            value: HashMap::new(),
+            contents: Vec::new(),
+            weightless_contents: false,
+        }
+    }
+
+    pub fn with_value(mut self, count: i64, coin: Coin) -> Self {
+        *self.value.entry(coin).or_insert(0) += count;
+        self
+    }
+
+    pub fn with_weightless_contents(mut self) -> Self {
+        self.weightless_contents = true;
+        self
+    }
+
+    pub fn push(&mut self, item: Item) {
+        self.contents.push(item)
+    }
+
+    /// Weight of all of these items along with anything inside them.
+    pub fn total_weight(&self) -> f64 {
+        let own = self.weight * f64::from(self.quantity);
+        if self.weightless_contents {
+            own
+        } else {
+            own + self.contents.iter().map(Item::total_weight).sum::<f64>()
+        }
+    }
+
+    /// Value of all of these items along with anything inside them.
+    pub fn total_value(&self) -> HashMap<Coin, i64> {
+        let mut value: HashMap<Coin, i64> = self
+            .value
+            .iter()
+            .map(|(coin, count)| (*coin, count * i64::from(self.quantity)))
+            .collect();
+        for item in self.contents.iter() {
+            add_coins(&mut value, &item.total_value());
+        }
+        value
+    }
+}
+
+fn add_coins(total: &mut HashMap<Coin, i64>, coins: &HashMap<Coin, i64>) {
+    for (coin, count) in coins.iter() {
+        *total.entry(*coin).or_insert(0) += count;
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EncumbranceRule {
+    Standard,
+    Variant,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, AsStaticStr)]
+pub enum Encumbrance {
+    Unencumbered,
+    Encumbered,
+    HeavilyEncumbered,
+    OverCapacity,
+}
+
+impl Encumbrance {
+    /// How much walking speed drops by, in feet. Over capacity, speed is
+    /// capped instead, see `speed_cap`.
+    pub fn speed_penalty(&self) -> i64 {
+        match self {
+            Encumbrance::Unencumbered | Encumbrance::OverCapacity => 0,
+            Encumbrance::Encumbered => 10,
+            Encumbrance::HeavilyEncumbered => 20,
+        }
+    }
+
+    /// The most walking speed can be, in feet.
+    pub fn speed_cap(&self) -> Option<i64> {
+        match self {
+            Encumbrance::OverCapacity => Some(OVER_CAPACITY_SPEED),
+            _ => None,
+        }
+    }
+
+    /// Walking speed for a creature with a `base` speed, in feet.
+    pub fn speed(&self, base: i64) -> i64 {
+        let speed = (base - self.speed_penalty()).max(0);
+        match self.speed_cap() {
+            Some(cap) => speed.min(cap),
+            None => speed,
+        }
+    }
+}
+
+/// Encumbrance when carrying `weight` pounds with the given Strength score.
+pub fn encumbrance(weight: f64, strength: i64, rule: EncumbranceRule) -> Encumbrance {
+    let strength = strength.max(0) as f64;
+    if weight > strength * CAPACITY_PER_STRENGTH {
+        Encumbrance::OverCapacity
+    } else if rule == EncumbranceRule::Standard {
+        Encumbrance::Unencumbered
+    } else if weight > strength * HEAVILY_ENCUMBERED_PER_STRENGTH {
+        Encumbrance::HeavilyEncumbered
+    } else if weight > strength * ENCUMBERED_PER_STRENGTH {
+        Encumbrance::Encumbered
+    } else {
+        Encumbrance::Unencumbered
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Inventory {
+    items: Vec<Item>,
+}
+
+impl Inventory {
+    pub fn new() -> Self {
+        Self { items: Vec::new() }
+    }
+
+    pub fn items(&self) -> &Vec<Item> {
+        &self.items
+    }
+
+    pub fn push(&mut self, item: Item) {
+        self.items.push(item)
+    }
+
+    /// Put `item` inside the first container named `container`, searching
+    /// nested containers too. Gives the item back if there's no such container.
+    pub fn push_into(&mut self, container: &str, item: Item) -> Result<(), Item> {
+        match find_mut(&mut self.items, container) {
+            Some(found) => {
+                found.push(item);
+                Ok(())
+            }
+            None => Err(item),
+        }
+    }
+
+    /// Remove the first item named `name`, searching nested containers too.
+    pub fn remove(&mut self, name: &str) -> Option<Item> {
+        remove(&mut self.items, name)
+    }
+
+    pub fn weight(&self) -> f64 {
+        self.items.iter().map(Item::total_weight).sum()
+    }
+
+    pub fn value(&self) -> HashMap<Coin, i64> {
+        let mut value = HashMap::new();
+        for item in self.items.iter() {
+            add_coins(&mut value, &item.total_value());
+        }
+        value
+    }
+
+    /// Total value in copper.
+    pub fn value_in_copper(&self) -> i64 {
+        self.value()
+            .iter()
+            .map(|(coin, count)| coin.value_from_count(*count))
+            .sum()
+    }
+
+    pub fn encumbrance(&self, strength: i64, rule: EncumbranceRule) -> Encumbrance {
+        encumbrance(self.weight(), strength, rule)
+    }
+}
+
+fn find_mut<'a>(items: &'a mut [Item], name: &str) -> Option<&'a mut Item> {
+    for item in items.iter_mut() {
+        if item.name == name {
+            return Some(item);
+        }
+        if let Some(found) = find_mut(&mut item.contents, name) {
+            return Some(found);
+        }
+    }
+    None
+}
+
+fn remove(items: &mut Vec<Item>, name: &str) -> Option<Item> {
+    if let Some(i) = items.iter().position(|item| item.name == name) {
+        return Some(items.remove(i));
+    }
+    items
+        .iter_mut()
+        .find_map(|item| remove(&mut item.contents, name))
+}
@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config;
+use crate::table::LoadError;
+use crate::time::{Time, ROUND};
+
+/// Something a global shortcut does.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Action {
+    /// Move world time forward by this many seconds.
+    Advance(i64),
+    /// Switch to the tab at this index, starting at 0.
+    Tab(u32),
+    NextTab,
+    PreviousTab,
+    /// Show or hide the list of shortcuts.
+    Help,
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Action::Advance(ROUND) => write!(f, "Advance 1 round"),
+            Action::Advance(seconds) => {
+                let duration = Time { value: *seconds };
+                write!(f, "Advance {}", duration.units().map_err(|_| fmt::Error)?)
+            }
+            Action::Tab(i) => write!(f, "Go to tab {}", i + 1),
+            Action::NextTab => write!(f, "Next tab"),
+            Action::PreviousTab => write!(f, "Previous tab"),
+            Action::Help => write!(f, "Show shortcuts"),
+        }
+    }
+}
+
+/// A key combination in GTK accelerator form, e.g. "<Primary>1" or "F5".
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Shortcut {
+    pub keys: String,
+    pub action: Action,
+}
+
+/// Keys used across the app, as loaded from `config_path`. Anything left
+/// out of the file keeps its default.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyBindings {
+    /// Amount a key adds or removes when released on a counter's + or -
+    /// button, by GDK key name.
+    #[serde(default = "default_counter_steps")]
+    pub counter_steps: BTreeMap<String, i64>,
+    #[serde(default = "default_shortcuts")]
+    pub shortcuts: Vec<Shortcut>,
+}
+
+impl Default for KeyBindings {
+    /// The bindings shipped in `data/keys/default.json`.
+    fn default() -> Self {
+        Self::from_json(include_str!("../data/keys/default.json")).expect("Default keys")
+    }
+}
+
+fn default_counter_steps() -> BTreeMap<String, i64> {
+    KeyBindings::default().counter_steps
+}
+
+fn default_shortcuts() -> Vec<Shortcut> {
+    KeyBindings::default().shortcuts
+}
+
+impl KeyBindings {
+    pub fn from_json(json: &str) -> Result<Self, LoadError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, LoadError> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+
+    /// Bindings from the user's config file, or the defaults without one.
+    pub fn load() -> Result<Self, LoadError> {
+        match config_path() {
+            Some(path) if path.exists() => Self::from_file(path),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    /// Amount added by releasing `key` on a counter button.
+    pub fn step(&self, key: &str) -> Option<i64> {
+        self.counter_steps.get(key).copied()
+    }
+
+    /// Keys bound to `action`, for showing in menus and help.
+    pub fn keys_for(&self, action: Action) -> impl Iterator<Item = &str> {
+        self.shortcuts
+            .iter()
+            .filter(move |s| s.action == action)
+            .map(|s| s.keys.as_str())
+    }
+}
+
+/// Where the user's key bindings live, e.g. `~/.config/dm-toolkit/keys.json`.
+pub fn config_path() -> Option<PathBuf> {
+    config::path("keys.json")
+}
@@ -0,0 +1,116 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::table::LoadError;
+
+/// Experience needed to reach each level, starting at level 1.
+pub const XP_LEVELS: [i64; 20] = [
+    0, 300, 900, 2700, 6500, 14000, 23000, 34000, 48000, 64000, 85000, 100000, 120000, 140000,
+    165000, 195000, 225000, 265000, 305000, 355000,
+];
+
+pub fn level_for_xp(xp: i64) -> u8 {
+    XP_LEVELS
+        .iter()
+        .filter(|needed| xp >= **needed)
+        .count()
+        .max(1) as u8
+}
+
+/// Experience needed for the level after `level`, if there is one.
+pub fn xp_for_next_level(level: u8) -> Option<i64> {
+    XP_LEVELS.get(level as usize).copied()
+}
+
+/// Name of the resource tracking spell slots of `level`, e.g. "3rd level slots".
+pub fn slot_name(level: u8) -> String {
+    let suffix = match level {
+        1 => "st",
+        2 => "nd",
+        3 => "rd",
+        _ => "th",
+    };
+    format!("{}{} level slots", level, suffix)
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HitPoints {
+    Rolled,
+    Average,
+}
+
+/// What changed when a character gained a level.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LevelUp {
+    pub level: u8,
+    pub hp: i64,
+    pub proficiency_bonus: i64,
+    /// Spell slots gained, by slot level.
+    pub slots: Vec<(u8, u32)>,
+}
+
+/// How a class advances, as loaded from a data file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClassProgression {
+    pub class: String,
+    pub hit_die: u32,
+    /// Spell slots by slot level, one row per character level.
+    #[serde(default)]
+    pub spell_slots: Vec<Vec<u32>>,
+}
+
+impl ClassProgression {
+    pub fn slots(&self, level: u8) -> &[u32] {
+        (level as usize)
+            .checked_sub(1)
+            .and_then(|i| self.spell_slots.get(i))
+            .map_or(&[], Vec::as_slice)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Progressions {
+    classes: Vec<ClassProgression>,
+}
+
+impl Progressions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The classes shipped in `data/classes/srd.json`.
+    pub fn srd() -> Self {
+        Self::from_json(include_str!("../data/classes/srd.json")).expect("SRD classes")
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, LoadError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, LoadError> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+
+    /// Add the classes from another file, e.g. homebrew.
+    pub fn load<P: AsRef<Path>>(&mut self, path: P) -> Result<(), LoadError> {
+        let other = Self::from_file(path)?;
+        self.classes.extend(other.classes);
+        Ok(())
+    }
+
+    pub fn push(&mut self, class: ClassProgression) {
+        self.classes.push(class)
+    }
+
+    pub fn classes(&self) -> &Vec<ClassProgression> {
+        &self.classes
+    }
+
+    pub fn find(&self, class: &str) -> Option<&ClassProgression> {
+        self.classes
+            .iter()
+            .find(|c| c.class.eq_ignore_ascii_case(class.trim()))
+    }
+}
@@ -1,4 +1,19 @@
+pub mod calendar;
+pub mod character;
+pub mod coin;
+pub mod combat;
+pub mod config;
+pub mod dice;
+pub mod encounter;
+pub mod inventory;
+pub mod keys;
+pub mod level;
+pub mod mob;
+pub mod preset;
+pub mod resource;
+pub mod table;
 pub mod time;
+pub mod treasure;
 pub mod ui;
 pub mod unit;
 pub mod world;
@@ -0,0 +1,47 @@
+/// The d20 roll an attacker with `attack_bonus` needs to hit `ac`.
+pub fn roll_needed(ac: i64, attack_bonus: i64) -> i64 {
+    ac - attack_bonus
+}
+
+/// Attackers needed for one of them to hit, from the DMG mob attacks table.
+pub fn attackers_per_hit(ac: i64, attack_bonus: i64) -> u32 {
+    match roll_needed(ac, attack_bonus) {
+        i64::MIN..=5 => 1,
+        6..=12 => 2,
+        13..=14 => 3,
+        15..=16 => 4,
+        17..=18 => 5,
+        19 => 10,
+        _ => 20,
+    }
+}
+
+/// Hits landed by `attackers` using the DMG mob attacks table.
+pub fn table_hits(attackers: u32, ac: i64, attack_bonus: i64) -> u32 {
+    attackers / attackers_per_hit(ac, attack_bonus)
+}
+
+/// Chance of a single attack hitting, counting natural 1s as misses and 20s as hits.
+pub fn hit_chance(ac: i64, attack_bonus: i64) -> f64 {
+    let faces = 21 - roll_needed(ac, attack_bonus);
+    faces.clamp(1, 19) as f64 / 20.0
+}
+
+pub fn expected_hits(attackers: u32, ac: i64, attack_bonus: i64) -> f64 {
+    attackers as f64 * hit_chance(ac, attack_bonus)
+}
+
+/// Probability of exactly `k` hits for each `k` from 0 to `attackers`.
+pub fn hit_distribution(attackers: u32, ac: i64, attack_bonus: i64) -> Vec<f64> {
+    let p = hit_chance(ac, attack_bonus);
+    let n = attackers as i32;
+    let mut binomial = 1.0;
+    (0..=n)
+        .map(|k| {
+            if k > 0 {
+                binomial *= f64::from(n - k + 1) / f64::from(k);
+            }
+            binomial * p.powi(k) * (1.0 - p).powi(n - k)
+        })
+        .collect()
+}
@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config;
+use crate::resource::Rest;
+use crate::table::LoadError;
+use crate::time::{Time, UnitTime, ROUND};
+
+/// Hours of travel in a day before the party risks exhaustion.
+pub const TRAVEL_DAY: i64 = 8;
+
+/// A named amount of time to advance by in one step.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Preset {
+    pub name: String,
+    pub duration: Time,
+}
+
+impl Preset {
+    pub fn new(name: &str, duration: Time) -> Self {
+        Self {
+            name: name.to_string(),
+            duration,
+        }
+    }
+
+    /// The presets always available: 1 round, 1 min, 10 min, 1 h, short
+    /// rest, long rest, 1 day and a travel day.
+    pub fn builtin() -> Vec<Preset> {
+        vec![
+            Preset::new("1 round", Time { value: ROUND }),
+            Preset::new("1 min", Time::from(1, UnitTime::Minute)),
+            Preset::new("10 min", Time::from(10, UnitTime::Minute)),
+            Preset::new("1 h", Time::from(1, UnitTime::Hour)),
+            Preset::new("Short rest", Rest::Short.duration()),
+            Preset::new("Long rest", Rest::Long.duration()),
+            Preset::new("1 day", Time::from(1, UnitTime::Day)),
+            Preset::new("Travel day", Time::from(TRAVEL_DAY, UnitTime::Hour)),
+        ]
+    }
+}
+
+/// Presets the user added on top of the built-in ones, as saved in
+/// `config_path`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Presets {
+    pub custom: Vec<Preset>,
+}
+
+impl Presets {
+    pub fn from_json(json: &str) -> Result<Self, LoadError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, LoadError> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+
+    /// The user's presets, or none without a config file.
+    pub fn load() -> Result<Self, LoadError> {
+        match config_path() {
+            Some(path) if path.exists() => Self::from_file(path),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Write the user's presets to `path`, creating its directory.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), LoadError> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+
+    /// Built-in presets followed by the user's, in the order added.
+    pub fn all(&self) -> Vec<Preset> {
+        let mut all = Preset::builtin();
+        all.extend(self.custom.iter().cloned());
+        all
+    }
+
+    /// Add `preset`, replacing any of the user's presets with the same name.
+    pub fn add(&mut self, preset: Preset) {
+        match self.custom.iter_mut().find(|p| p.name == preset.name) {
+            Some(existing) => *existing = preset,
+            None => self.custom.push(preset),
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Preset> {
+        let i = self.custom.iter().position(|p| p.name == name)?;
+        Some(self.custom.remove(i))
+    }
+}
+
+/// Where the user's presets live, e.g. `~/.config/dm-toolkit/presets.json`.
+pub fn config_path() -> Option<PathBuf> {
+    config::path("presets.json")
+}
@@ -0,0 +1,171 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use strum_macros::AsStaticStr;
+
+use crate::time::{Time, UnitTime};
+
+/// When a spent resource comes back.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Recovery {
+    ShortRest,
+    LongRest,
+    Dawn,
+    /// Regained on a d6 roll of at least this, e.g. 5 for "Recharge 5-6".
+    Recharge(u8),
+}
+
+impl fmt::Display for Recovery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Recovery::ShortRest => write!(f, "Short rest"),
+            Recovery::LongRest => write!(f, "Long rest"),
+            Recovery::Dawn => write!(f, "Dawn"),
+            Recovery::Recharge(6) => write!(f, "Recharge 6"),
+            Recovery::Recharge(min) => write!(f, "Recharge {}-6", min),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, AsStaticStr)]
+pub enum Rest {
+    Short,
+    Long,
+}
+
+impl Rest {
+    pub fn duration(&self) -> Time {
+        match self {
+            Rest::Short => Time::from(1, UnitTime::Hour),
+            Rest::Long => Time::from(8, UnitTime::Hour),
+        }
+    }
+
+    /// Whether resting this way restores resources with `recovery`.
+    /// Recharge abilities are always ready again after resting.
+    pub fn restores(&self, recovery: Recovery) -> bool {
+        match recovery {
+            Recovery::ShortRest | Recovery::Recharge(_) => true,
+            Recovery::LongRest => *self == Rest::Long,
+            Recovery::Dawn => false,
+        }
+    }
+}
+
+/// Spell slots, Ki, item charges and the like.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Resource {
+    pub name: String,
+    pub current: u32,
+    pub max: u32,
+    pub recovery: Recovery,
+}
+
+impl Resource {
+    pub fn new(name: &str, max: u32, recovery: Recovery) -> Self {
+        Self {
+            name: name.to_string(),
+            current: max,
+            max,
+            recovery,
+        }
+    }
+
+    /// Spend `amount` uses, failing without spending any if there aren't enough.
+    pub fn spend(&mut self, amount: u32) -> bool {
+        if amount > self.current {
+            return false;
+        }
+        self.current -= amount;
+        true
+    }
+
+    pub fn restore(&mut self) {
+        self.current = self.max;
+    }
+
+    /// Roll to recharge, returning whether it came back.
+    /// Resources that don't recharge on a roll are left alone.
+    pub fn roll_recharge<R: Rng>(&mut self, rng: &mut R) -> bool {
+        match self.recovery {
+            Recovery::Recharge(min) if self.current < self.max => {
+                let recharged = rng.gen_range(1, 7) >= min;
+                if recharged {
+                    self.restore();
+                }
+                recharged
+            }
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Resources {
+    resources: Vec<Resource>,
+}
+
+impl Resources {
+    pub fn new() -> Self {
+        Self {
+            resources: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, resource: Resource) {
+        self.resources.push(resource)
+    }
+
+    pub fn resources(&self) -> &Vec<Resource> {
+        &self.resources
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Resource> {
+        self.resources.iter().find(|r| r.name == name)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Resource> {
+        self.resources.iter_mut().find(|r| r.name == name)
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Resource> {
+        let i = self.resources.iter().position(|r| r.name == name)?;
+        Some(self.resources.remove(i))
+    }
+
+    pub fn spend(&mut self, name: &str, amount: u32) -> bool {
+        self.get_mut(name).is_some_and(|r| r.spend(amount))
+    }
+
+    /// Restore everything regained by taking `rest`.
+    pub fn rest(&mut self, rest: Rest) {
+        for resource in self.resources.iter_mut() {
+            if rest.restores(resource.recovery) {
+                resource.restore();
+            }
+        }
+    }
+
+    pub fn dawn(&mut self) {
+        for resource in self.resources.iter_mut() {
+            if resource.recovery == Recovery::Dawn {
+                resource.restore();
+            }
+        }
+    }
+
+    /// Roll for every recharge resource, returning the names of those regained.
+    pub fn roll_recharge<R: Rng>(&mut self, rng: &mut R) -> Vec<String> {
+        self.resources
+            .iter_mut()
+            .filter_map(|r| {
+                if r.roll_recharge(rng) {
+                    Some(r.name.clone())
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,237 @@
+use rand::distributions::WeightedIndex;
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::config;
+use crate::dice::Dice;
+use crate::encounter::{ChallengeRating, Difficulty, Encounter, Monster};
+
+/// How many times to reroll quantities before giving up on a difficulty.
+const ATTEMPTS: usize = 100;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TableEntry {
+    pub weight: u32,
+    pub quantity: Dice,
+    pub name: String,
+    pub cr: ChallengeRating,
+}
+
+impl TableEntry {
+    fn monster(&self, count: i64) -> Monster {
+        Monster::new(&self.name, self.cr, count.max(1) as usize)
+    }
+
+    fn quantities(&self) -> impl Iterator<Item = i64> {
+        self.quantity.min().max(1)..=self.quantity.max().max(1)
+    }
+}
+
+/// Weighted encounters for one terrain and range of challenge ratings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EncounterTable {
+    pub terrain: String,
+    pub min_cr: ChallengeRating,
+    pub max_cr: ChallengeRating,
+    pub entries: Vec<TableEntry>,
+}
+
+impl EncounterTable {
+    pub fn covers(&self, cr: ChallengeRating) -> bool {
+        self.min_cr <= cr && cr <= self.max_cr
+    }
+
+    /// How far one monster of the table's CR band is from `xp`, as a ratio:
+    /// 1 when the band covers it, growing as the band gets too weak or strong.
+    pub fn distance(&self, xp: i64) -> f64 {
+        let xp = xp.max(1) as f64;
+        let (min, max) = (self.min_cr.xp() as f64, self.max_cr.xp() as f64);
+        if xp < min {
+            min / xp
+        } else if xp > max {
+            xp / max
+        } else {
+            1.0
+        }
+    }
+
+    /// Pick an entry by weight and roll how many monsters appear.
+    pub fn roll<R: Rng>(&self, rng: &mut R) -> Option<Monster> {
+        let entry = pick(self.entries.iter(), rng)?;
+        Some(entry.monster(entry.quantity.roll(rng)))
+    }
+
+    /// Roll an encounter against `party` that is exactly `difficulty`.
+    ///
+    /// Only entries that can reach the difficulty with some quantity are considered.
+    pub fn generate<R: Rng>(
+        &self,
+        party: &[u8],
+        difficulty: Difficulty,
+        rng: &mut R,
+    ) -> Option<Encounter> {
+        let fits = |monster: Monster| {
+            let encounter = Encounter::new(party.to_vec(), vec![monster]);
+            Some(encounter).filter(|e| e.difficulty() == difficulty)
+        };
+        let candidates = self
+            .entries
+            .iter()
+            .filter(|entry| entry.quantities().any(|q| fits(entry.monster(q)).is_some()));
+        let entry = pick(candidates, rng)?;
+
+        (0..ATTEMPTS)
+            .map(|_| entry.monster(entry.quantity.roll(rng)))
+            .find_map(fits)
+    }
+}
+
+fn pick<'a, R, I>(entries: I, rng: &mut R) -> Option<&'a TableEntry>
+where
+    R: Rng,
+    I: Iterator<Item = &'a TableEntry>,
+{
+    let entries: Vec<&TableEntry> = entries.filter(|e| e.weight > 0).collect();
+    let weights = WeightedIndex::new(entries.iter().map(|e| e.weight)).ok()?;
+    Some(entries[weights.sample(rng)])
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(io::Error),
+    Parse(serde_json::Error),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadError::Io(e) => write!(f, "could not read table: {}", e),
+            LoadError::Parse(e) => write!(f, "could not parse table: {}", e),
+        }
+    }
+}
+
+impl From<io::Error> for LoadError {
+    fn from(e: io::Error) -> Self {
+        LoadError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for LoadError {
+    fn from(e: serde_json::Error) -> Self {
+        LoadError::Parse(e)
+    }
+}
+
+/// A collection of encounter tables, usually loaded from one data file per region.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct EncounterTables {
+    tables: Vec<EncounterTable>,
+}
+
+impl EncounterTables {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The tables shipped in `data/encounters`.
+    pub fn bundled() -> Self {
+        Self::from_json(include_str!("../data/encounters/forest.json")).expect("Bundled tables")
+    }
+
+    /// The bundled tables plus the user's from `config_path`, if any.
+    pub fn load_all() -> Result<Self, LoadError> {
+        let mut tables = Self::bundled();
+        match config_path() {
+            Some(path) if path.exists() => tables.load(path)?,
+            _ => {}
+        }
+        Ok(tables)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, LoadError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, LoadError> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+
+    /// Add the tables from another file, e.g. a region authored by the DM.
+    pub fn load<P: AsRef<Path>>(&mut self, path: P) -> Result<(), LoadError> {
+        let other = Self::from_file(path)?;
+        self.tables.extend(other.tables);
+        Ok(())
+    }
+
+    pub fn push(&mut self, table: EncounterTable) {
+        self.tables.push(table)
+    }
+
+    pub fn tables(&self) -> &Vec<EncounterTable> {
+        &self.tables
+    }
+
+    pub fn terrains(&self) -> Vec<&str> {
+        let mut terrains: Vec<&str> = self.tables.iter().map(|t| t.terrain.as_str()).collect();
+        terrains.sort();
+        terrains.dedup();
+        terrains
+    }
+
+    /// Tables for `terrain`, those whose CR band best matches the party's
+    /// XP budget for `difficulty` first.
+    pub fn find(
+        &self,
+        terrain: &str,
+        party: &[u8],
+        difficulty: Difficulty,
+    ) -> Vec<&EncounterTable> {
+        let budget = Encounter::new(party.to_vec(), vec![]).threshold(difficulty);
+        let mut tables: Vec<&EncounterTable> = self
+            .tables
+            .iter()
+            .filter(|t| t.terrain.eq_ignore_ascii_case(terrain))
+            .collect();
+        tables.sort_by(|a, b| {
+            a.distance(budget)
+                .partial_cmp(&b.distance(budget))
+                .expect("Ratio")
+        });
+        tables
+    }
+
+    /// Roll an encounter from the best matching table that can reach
+    /// `difficulty`, picking at random between tables that match as well.
+    pub fn generate<R: Rng>(
+        &self,
+        terrain: &str,
+        party: &[u8],
+        difficulty: Difficulty,
+        rng: &mut R,
+    ) -> Option<Encounter> {
+        let budget = Encounter::new(party.to_vec(), vec![]).threshold(difficulty);
+        let mut tables = self.find(terrain, party, difficulty);
+        tables.shuffle(rng);
+        // Stable, so tables that match as well stay shuffled.
+        tables.sort_by(|a, b| {
+            a.distance(budget)
+                .partial_cmp(&b.distance(budget))
+                .expect("Ratio")
+        });
+        tables
+            .iter()
+            .find_map(|t| t.generate(party, difficulty, rng))
+    }
+}
+
+/// Where the user's encounter tables live, e.g.
+/// `~/.config/dm-toolkit/encounters.json`.
+pub fn config_path() -> Option<PathBuf> {
+    config::path("encounters.json")
+}
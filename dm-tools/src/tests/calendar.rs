@@ -0,0 +1,77 @@
+use crate::calendar::*;
+use crate::time::UnitTime::*;
+use crate::time::{Category, Event, Time};
+
+#[test]
+fn date_from_time() {
+    let time = Time {
+        value: Time::from(1, Year).value + Time::from(33, Day).value + Time::from(5, Hour).value,
+    };
+    let date = Date::from_time(&time);
+    assert_eq!(
+        date,
+        Date {
+            year: 1,
+            month: 1,
+            day: 5
+        }
+    );
+    assert_eq!(Date::from_index(date.index()), date);
+    assert_eq!(date.start().value, time.value - Time::from(5, Hour).value);
+}
+
+#[test]
+fn weekdays_repeat() {
+    let first = Date::from_index(0);
+    assert_eq!(first.weekday(), 0);
+    assert_eq!(Date::from_index(9).weekday(), 2);
+    // Months are whole weeks, so each starts on the same day.
+    assert_eq!(first.add_months(5).weekday(), 0);
+}
+
+#[test]
+fn add_months_wraps_years() {
+    let date = Date {
+        year: 0,
+        month: 11,
+        day: 3,
+    };
+    assert_eq!(
+        date.add_months(1),
+        Date {
+            year: 1,
+            month: 0,
+            day: 3
+        }
+    );
+    assert_eq!(date.add_months(-12).year, -1);
+}
+
+#[test]
+fn event_days() {
+    let event = Event {
+        start: Time::from(20, Hour),
+        end: Time::from(50, Hour),
+        id: 1,
+        name: "Festival".to_string(),
+        category: Category::Other,
+    };
+    let days: Vec<i64> = Date::days_of(&event).map(|d| d.index()).collect();
+    assert_eq!(days, vec![0, 1, 2]);
+}
+
+#[test]
+fn default_calendar() {
+    let calendar = Calendar::default();
+    let date = Date::from_index(30);
+    assert_eq!(calendar.describe(&date), "Windsday 3 Thawmoon, year 0");
+}
+
+#[test]
+fn calendar_needs_every_month() {
+    let result = Calendar::from_json(r#"{ "months": ["One"], "weekdays": [] }"#);
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("expected 12 months"));
+}
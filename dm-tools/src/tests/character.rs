@@ -0,0 +1,115 @@
+use crate::character::Ability::*;
+use crate::character::*;
+
+fn fighter() -> Character {
+    let mut character = Character::new("Tordek", "Fighter", 5);
+    character.abilities = AbilityScores {
+        strength: 16,
+        dexterity: 13,
+        constitution: 15,
+        intelligence: 8,
+        wisdom: 11,
+        charisma: 9,
+    };
+    character.saves.insert(Strength);
+    character.saves.insert(Constitution);
+    character.skills.insert(Skill::Athletics);
+    character.skills.insert(Skill::Perception);
+    character
+}
+
+#[test]
+fn ability_modifiers() {
+    let table = [
+        (1, -5),
+        (2, -4),
+        (3, -4),
+        (8, -1),
+        (9, -1),
+        (10, 0),
+        (11, 0),
+        (12, 1),
+        (15, 2),
+        (20, 5),
+        (30, 10),
+    ];
+    for (score, expected) in table.iter() {
+        assert_eq!(modifier(*score), *expected, "score {}", score);
+    }
+}
+
+#[test]
+fn proficiency_bonus_by_level() {
+    let table = [
+        (1, 2),
+        (4, 2),
+        (5, 3),
+        (8, 3),
+        (9, 4),
+        (12, 4),
+        (13, 5),
+        (16, 5),
+        (17, 6),
+        (20, 6),
+    ];
+    for (level, bonus) in table.iter() {
+        let character = Character::new("", "", *level);
+        assert_eq!(character.proficiency_bonus(), *bonus, "level {}", level);
+    }
+}
+
+#[test]
+fn level_clamped() {
+    assert_eq!(Character::new("", "", 0).level, 1);
+    assert_eq!(Character::new("", "", 25).level, 20);
+}
+
+#[test]
+fn ability_scores_get_set() {
+    let mut scores = AbilityScores::default();
+    for (i, ability) in Ability::variants().iter().enumerate() {
+        scores.set(*ability, i as i64);
+    }
+    for (i, ability) in Ability::variants().iter().enumerate() {
+        assert_eq!(scores.get(*ability), i as i64);
+    }
+}
+
+#[test]
+fn saving_throws() {
+    let character = fighter();
+    assert_eq!(character.save(Strength), 6);
+    assert_eq!(character.save(Constitution), 5);
+    assert_eq!(character.save(Dexterity), 1);
+    assert_eq!(character.save(Intelligence), -1);
+}
+
+#[test]
+fn skill_bonuses() {
+    let character = fighter();
+    assert_eq!(character.skill(Skill::Athletics), 6);
+    assert_eq!(character.skill(Skill::Acrobatics), 1);
+    assert_eq!(character.skill(Skill::Perception), 3);
+    assert_eq!(character.skill(Skill::Persuasion), -1);
+}
+
+#[test]
+fn skills_use_their_ability() {
+    let table = [
+        (Skill::Athletics, Strength),
+        (Skill::Stealth, Dexterity),
+        (Skill::Arcana, Intelligence),
+        (Skill::Survival, Wisdom),
+        (Skill::Deception, Charisma),
+    ];
+    for (skill, ability) in table.iter() {
+        assert_eq!(skill.ability(), *ability);
+    }
+    assert_eq!(Skill::variants().len(), 18);
+}
+
+#[test]
+fn passive_perception() {
+    assert_eq!(fighter().passive_perception(), 13);
+    assert_eq!(Character::new("", "", 1).passive_perception(), 10);
+}
@@ -0,0 +1,156 @@
+use crate::coin::Coin::*;
+use crate::coin::*;
+use crate::unit::{CountError, Unit};
+
+fn purse(coins: &[(i64, Coin)]) -> Purse {
+    let mut purse = Purse::new();
+    for (count, coin) in coins.iter() {
+        purse.add_coins(*count, *coin).unwrap();
+    }
+    purse
+}
+
+#[test]
+fn coin_values() {
+    assert_eq!(
+        Coin::variants(),
+        &[Copper, Silver, Electrum, Gold, Platinum]
+    );
+    assert_eq!(Gold.value(), 100);
+    assert_eq!(Platinum.value(), 1000);
+}
+
+#[test]
+fn coin_from_str() {
+    assert_eq!("gp".parse::<Coin>().unwrap(), Gold);
+    assert_eq!("Electrum".parse::<Coin>().unwrap(), Electrum);
+    assert!("doubloon".parse::<Coin>().is_err());
+}
+
+#[test]
+fn parse_amounts() {
+    assert_eq!(parse_amount("7 gp 5 sp").unwrap(), 750);
+    assert_eq!(parse_amount("1 pp 1 ep 3 cp").unwrap(), 1053);
+    assert!(matches!(
+        parse_amount("7"),
+        Err(CountError::InvalidValue { .. })
+    ));
+    assert!(matches!(
+        parse_amount("-1 gp"),
+        Err(CountError::InvalidValue { .. })
+    ));
+    assert_eq!(
+        parse_amount("1 xp"),
+        Err(CountError::UnitNotFound("xp".to_string()))
+    );
+}
+
+#[test]
+fn purse_total() {
+    let purse = purse(&[(3, Gold), (4, Silver), (2, Copper)]);
+    assert_eq!(purse.total(), 342);
+}
+
+#[test]
+fn purse_never_negative() {
+    let mut purse = purse(&[(1, Gold)]);
+    assert!(purse.add_coins(-3, Gold).is_err());
+    assert_eq!(purse.get_count(Gold), 1);
+}
+
+#[test]
+fn pay_exact() {
+    let mut purse = purse(&[(10, Gold), (6, Silver)]);
+    let change = purse.pay(parse_amount("7 gp 5 sp").unwrap()).unwrap();
+    assert!(change.is_empty());
+    assert_eq!(purse.get_count(Gold), 3);
+    assert_eq!(purse.get_count(Silver), 1);
+}
+
+#[test]
+fn pay_makes_change() {
+    let mut purse = purse(&[(10, Gold)]);
+    let change = purse.pay(parse_amount("7 gp 5 sp").unwrap()).unwrap();
+    // 8 gp handed over, 5 sp back as a single electrum.
+    assert_eq!(change.get(&Electrum), Some(&1));
+    assert_eq!(purse.get_count(Gold), 2);
+    assert_eq!(purse.get_count(Electrum), 1);
+    assert_eq!(purse.total(), 250);
+}
+
+#[test]
+fn pay_breaks_smallest_coin() {
+    let mut purse = purse(&[(1, Platinum), (1, Gold), (3, Copper)]);
+    purse.pay(5).unwrap();
+    assert_eq!(purse.get_count(Platinum), 1);
+    assert_eq!(purse.get_count(Gold), 0);
+    assert_eq!(purse.total(), 1098);
+}
+
+#[test]
+fn pay_not_enough() {
+    let mut purse = purse(&[(2, Gold)]);
+    assert_eq!(
+        purse.pay(201),
+        Err(CountError::NotEnough {
+            unit: "cp",
+            requested: 201,
+            available: 200,
+        })
+    );
+    assert_eq!(purse.get_count(Gold), 2);
+}
+
+#[test]
+fn format() {
+    let purse = purse(&[(7, Gold), (5, Silver)]);
+    assert_eq!(format_coins(&purse.counts()), "7 gp 5 sp");
+    assert_eq!(format_coins(&Default::default()), "0 cp");
+}
+
+#[test]
+fn set_from_text() {
+    let mut purse = purse(&[(5, Silver)]);
+    purse.set_from_text("+3", Silver).unwrap();
+    assert_eq!(purse.get_count(Silver), 8);
+    assert_eq!(
+        purse.set_from_text("-10", Silver),
+        Err(CountError::NotEnough {
+            unit: "sp",
+            requested: 10,
+            available: 8,
+        })
+    );
+    assert_eq!(purse.get_count(Silver), 8);
+    assert!(purse.set_from_text("-8", Silver).is_ok());
+    assert_eq!(purse.get_count(Silver), 0);
+    purse.set_from_text("12", Silver).unwrap();
+    assert_eq!(purse.get_count(Silver), 12);
+    assert!(purse.set_from_text("twelve", Silver).is_err());
+}
+
+#[test]
+fn set_coins_below_zero() {
+    let mut purse = purse(&[(3, Gold)]);
+    assert_eq!(
+        purse.set_coins(-2, Gold),
+        Err(CountError::NotEnough {
+            unit: "gp",
+            requested: 5,
+            available: 3,
+        })
+    );
+    assert_eq!(purse.get_count(Gold), 3);
+}
+
+#[test]
+fn error_messages() {
+    assert_eq!(
+        parse_amount("x gp").unwrap_err().to_string(),
+        "\"x\" isn't a valid amount: invalid digit found in string"
+    );
+    assert_eq!(
+        purse(&[(2, Gold)]).pay(250).unwrap_err().to_string(),
+        "not enough, needed 250 cp but only 200 cp available"
+    );
+}
@@ -0,0 +1,444 @@
+use crate::combat::*;
+use crate::dice::Dice;
+use crate::time::UnitTime::*;
+use crate::time::*;
+
+fn combat() -> Combat {
+    let mut combat = Combat::new(Time::from(1, Hour));
+    combat.add(Combatant::new("Goblin", 12, 7));
+    combat.add(Combatant::new("Fighter", 18, 30));
+    combat.add(Combatant::new("Wizard", 12, 14));
+    combat
+}
+
+fn names(combat: &Combat) -> Vec<&str> {
+    combat
+        .combatants()
+        .iter()
+        .map(|c| c.name.as_str())
+        .collect()
+}
+
+fn actions(combat: &Combat) -> Vec<&Action> {
+    combat.log().entries().iter().map(|e| &e.action).collect()
+}
+
+#[test]
+fn combat_orders_by_initiative() {
+    assert_eq!(names(&combat()), vec!["Fighter", "Goblin", "Wizard"]);
+}
+
+#[test]
+fn combat_not_started() {
+    let combat = combat();
+    assert_eq!(combat.round(), 0);
+    assert_eq!(combat.current(), None);
+    assert!(combat.log().entries().is_empty());
+}
+
+#[test]
+fn combat_turns_advance_rounds() {
+    let mut combat = combat();
+    combat.start();
+    assert_eq!(combat.current().unwrap().name, "Fighter");
+    combat.next_turn();
+    combat.next_turn();
+    assert_eq!(combat.current().unwrap().name, "Wizard");
+    assert_eq!(combat.round(), 1);
+    combat.next_turn();
+    assert_eq!(combat.current().unwrap().name, "Fighter");
+    assert_eq!(combat.round(), 2);
+}
+
+#[test]
+fn combat_time_by_round() {
+    let mut combat = combat();
+    combat.start();
+    assert_eq!(combat.time(), Time::from(1, Hour));
+    for _ in 0..3 {
+        combat.next_turn();
+    }
+    assert_eq!(combat.time().value, Time::from(1, Hour).value + ROUND);
+}
+
+#[test]
+fn combat_elapsed_counts_rounds_started() {
+    let mut combat = combat();
+    assert_eq!(combat.elapsed(), Time::new());
+    combat.set_start(Time::from(2, Hour));
+    combat.start();
+    for _ in 0..3 {
+        combat.next_turn();
+    }
+    assert_eq!(combat.elapsed().value, 2 * ROUND);
+    assert_eq!(combat.time().value, Time::from(2, Hour).value + ROUND);
+}
+
+#[test]
+fn combat_add_keeps_current_turn() {
+    let mut combat = combat();
+    combat.start();
+    combat.next_turn();
+    combat.add(Combatant::new("Rogue", 20, 20));
+    assert_eq!(combat.current().unwrap().name, "Goblin");
+}
+
+#[test]
+fn combat_damage_and_heal() {
+    let mut combat = combat();
+    combat.start();
+    combat.damage(1, 10, Some("Fighter"));
+    assert_eq!(combat.combatants()[1].hp, 0);
+    combat.heal(1, 20);
+    assert_eq!(combat.combatants()[1].hp, 7);
+}
+
+#[test]
+fn combat_condition_expires() {
+    let mut combat = combat();
+    combat.start();
+    combat.apply_condition(1, Condition::Prone, Some(1));
+    combat.next_turn();
+    assert!(combat.combatants()[1].has_condition(Condition::Prone));
+    for _ in 0..3 {
+        combat.next_turn();
+    }
+    assert!(!combat.combatants()[1].has_condition(Condition::Prone));
+    assert_eq!(
+        actions(&combat).last(),
+        Some(&&Action::ConditionExpired {
+            target: "Goblin".to_string(),
+            condition: Condition::Prone,
+        })
+    );
+}
+
+#[test]
+fn combat_condition_indefinite() {
+    let mut combat = combat();
+    combat.start();
+    combat.apply_condition(0, Condition::Charmed, None);
+    for _ in 0..9 {
+        combat.next_turn();
+    }
+    assert!(combat.combatants()[0].has_condition(Condition::Charmed));
+    combat.remove_condition(0, Condition::Charmed);
+    assert!(!combat.combatants()[0].has_condition(Condition::Charmed));
+}
+
+#[test]
+fn log_records_in_order() {
+    let mut combat = combat();
+    combat.start();
+    combat.record_roll("Fighter", "attack", Dice::new(1, 20, 5), 17);
+    combat.damage(1, 8, Some("Fighter"));
+    combat.next_turn();
+    combat.end();
+
+    let entries = combat.log().entries();
+    let rounds: Vec<u32> = entries.iter().map(|e| e.round).collect();
+    assert_eq!(rounds, vec![1; 6]);
+    assert_eq!(
+        actions(&combat),
+        vec![
+            &Action::Start,
+            &Action::Turn {
+                name: "Fighter".to_string()
+            },
+            &Action::Roll {
+                name: "Fighter".to_string(),
+                purpose: "attack".to_string(),
+                dice: Dice::new(1, 20, 5),
+                result: 17,
+            },
+            &Action::Damage {
+                source: Some("Fighter".to_string()),
+                target: "Goblin".to_string(),
+                amount: 8,
+                hp: 0,
+            },
+            &Action::Turn {
+                name: "Goblin".to_string()
+            },
+            &Action::End,
+        ]
+    );
+}
+
+#[test]
+fn log_markdown() {
+    let mut combat = combat();
+    combat.start();
+    combat.damage(1, 3, None);
+    for _ in 0..3 {
+        combat.next_turn();
+    }
+    let markdown = combat.log().to_markdown();
+    let expected = "# Combat log
+
+## Round 1
+
+- `Day 1, 01:00:00` Combat started
+- `Day 1, 01:00:00` Fighter's turn
+- `Day 1, 01:00:00` Goblin took 3 damage (4 HP)
+- `Day 1, 01:00:00` Goblin's turn
+- `Day 1, 01:00:00` Wizard's turn
+
+## Round 2
+
+- `Day 1, 01:00:06` Fighter's turn
+";
+    assert_eq!(markdown, expected);
+}
+
+#[test]
+fn log_json_round_trip() {
+    let mut combat = combat();
+    combat.start();
+    combat.apply_condition(2, Condition::Poisoned, Some(2));
+    combat.record_roll("Wizard", "save", Dice::new(1, 20, 0), 4);
+    let json = combat.log().to_json().unwrap();
+    assert!(json.contains(r#""type": "condition_applied""#));
+    let log: CombatLog = serde_json::from_str(&json).unwrap();
+    assert_eq!(&log, combat.log());
+}
+
+fn boss_fight() -> Combat {
+    let mut combat = Combat::new(Time::new());
+    combat.add(Combatant::new("Rogue", 22, 20));
+    combat.add(
+        Combatant::new("Dragon", 15, 200)
+            .with_legendary_actions(3)
+            .with_lair(),
+    );
+    combat.add(Combatant::new("Fighter", 20, 30));
+    combat.add(Combatant::new("Cleric", 8, 25));
+    combat
+}
+
+#[test]
+fn lair_action_loses_ties_at_twenty() {
+    let combat = boss_fight();
+    assert_eq!(
+        combat.turns(),
+        vec![
+            Turn::Combatant(0),
+            Turn::Combatant(1),
+            Turn::Lair(2),
+            Turn::Combatant(2),
+            Turn::Combatant(3),
+        ]
+    );
+}
+
+#[test]
+fn lair_action_after_everyone_above_twenty() {
+    let mut combat = Combat::new(Time::new());
+    combat.add(Combatant::new("Lich", 25, 135).with_lair());
+    assert_eq!(combat.turns(), vec![Turn::Combatant(0), Turn::Lair(0)]);
+}
+
+#[test]
+fn lair_action_turn_logged() {
+    let mut combat = boss_fight();
+    combat.start();
+    combat.next_turn();
+    combat.next_turn();
+    assert_eq!(combat.current_turn(), Some(Turn::Lair(2)));
+    assert_eq!(combat.current().unwrap().name, "Dragon");
+    assert_eq!(
+        actions(&combat).last(),
+        Some(&&Action::Lair {
+            name: "Dragon".to_string()
+        })
+    );
+    combat.next_turn();
+    assert_eq!(combat.current_turn(), Some(Turn::Combatant(2)));
+}
+
+#[test]
+fn legendary_available_between_other_turns() {
+    let mut combat = boss_fight();
+    combat.start();
+    assert_eq!(combat.legendary_available(), vec![2]);
+    for _ in 0..3 {
+        combat.next_turn();
+    }
+    assert_eq!(combat.current().unwrap().name, "Dragon");
+    assert_eq!(combat.legendary_available(), Vec::<usize>::new());
+}
+
+#[test]
+fn legendary_actions_spent_and_refreshed() {
+    let mut combat = boss_fight();
+    combat.start();
+    assert!(combat.use_legendary(2, 2));
+    assert!(!combat.use_legendary(2, 2));
+    assert!(combat.use_legendary(2, 1));
+    assert_eq!(combat.legendary_available(), Vec::<usize>::new());
+    for _ in 0..3 {
+        combat.next_turn();
+    }
+    assert_eq!(combat.combatants()[2].legendary_remaining, 3);
+}
+
+#[test]
+fn legendary_actions_need_to_act() {
+    let mut combat = boss_fight();
+    combat.start();
+    combat.apply_condition(2, Condition::Stunned, Some(1));
+    assert!(!combat.use_legendary(2, 1));
+    combat.damage(2, 200, None);
+    assert_eq!(combat.legendary_available(), Vec::<usize>::new());
+}
+
+#[test]
+fn add_during_lair_turn_keeps_turn() {
+    let mut combat = boss_fight();
+    combat.start();
+    combat.next_turn();
+    combat.next_turn();
+    combat.add(Combatant::new("Bard", 21, 18));
+    assert_eq!(combat.current_turn(), Some(Turn::Lair(3)));
+}
+
+fn goblin_fight() -> Combat {
+    let mut combat = Combat::new(Time::new());
+    combat.add(Combatant::new("Fighter", 18, 30));
+    combat.add_group("Goblin", 3, 12, 7);
+    combat.add(Combatant::new("Wizard", 10, 14));
+    combat
+}
+
+#[test]
+fn group_added_with_numbered_names() {
+    let combat = goblin_fight();
+    assert_eq!(
+        names(&combat),
+        vec!["Fighter", "Goblin 1", "Goblin 2", "Goblin 3", "Wizard"]
+    );
+    assert_eq!(combat.group_members("Goblin"), vec![1, 2, 3]);
+}
+
+#[test]
+fn group_ids_unique_across_batches() {
+    let mut combat = goblin_fight();
+    combat.add_group("Goblin", 2, 10, 7);
+    let goblins: Vec<_> = combat
+        .combatants()
+        .iter()
+        .filter(|c| c.name == "Goblin 1")
+        .map(|c| c.id)
+        .collect();
+    assert_eq!(goblins.len(), 2);
+    assert_ne!(goblins[0], goblins[1]);
+    let index = combat.position(goblins[1]).unwrap();
+    assert_eq!(combat.combatants()[index].id, goblins[1]);
+}
+
+#[test]
+fn group_initiative_shares_slot() {
+    let mut combat = goblin_fight();
+    assert_eq!(combat.turns().len(), 5);
+    combat.set_group_initiative(true);
+    assert_eq!(
+        combat.turns(),
+        vec![Turn::Combatant(0), Turn::Group(1), Turn::Combatant(4)]
+    );
+    assert_eq!(combat.members(Turn::Group(1)), vec![1, 2, 3]);
+}
+
+#[test]
+fn group_turn_logged_by_group() {
+    let mut combat = goblin_fight();
+    combat.set_group_initiative(true);
+    combat.start();
+    combat.next_turn();
+    assert_eq!(
+        actions(&combat).last(),
+        Some(&&Action::Turn {
+            name: "Goblin".to_string()
+        })
+    );
+    combat.next_turn();
+    assert_eq!(combat.current().unwrap().name, "Wizard");
+}
+
+#[test]
+fn group_turn_expires_member_conditions() {
+    let mut combat = goblin_fight();
+    combat.set_group_initiative(true);
+    combat.start();
+    combat.apply_condition(3, Condition::Prone, Some(1));
+    for _ in 0..4 {
+        combat.next_turn();
+    }
+    assert!(!combat.combatants()[3].has_condition(Condition::Prone));
+}
+
+#[test]
+fn group_initiative_toggle_keeps_turn() {
+    let mut combat = goblin_fight();
+    combat.start();
+    combat.next_turn();
+    combat.next_turn();
+    assert_eq!(combat.current().unwrap().name, "Goblin 2");
+    combat.set_group_initiative(true);
+    assert_eq!(combat.current_turn(), Some(Turn::Group(1)));
+    combat.next_turn();
+    assert_eq!(combat.current().unwrap().name, "Wizard");
+}
+
+#[test]
+fn group_initiative_toggle_keeps_lair_turn() {
+    let mut combat = Combat::new(Time::new());
+    combat.add(Combatant::new("Lich", 25, 135).with_lair());
+    combat.add_group("Skeleton", 2, 22, 13);
+    combat.start();
+    for _ in 0..3 {
+        combat.next_turn();
+    }
+    assert_eq!(combat.current_turn(), Some(Turn::Lair(0)));
+    combat.set_group_initiative(true);
+    assert_eq!(combat.current_turn(), Some(Turn::Lair(0)));
+    combat.set_group_initiative(false);
+    assert_eq!(combat.current_turn(), Some(Turn::Lair(0)));
+}
+
+#[test]
+fn add_same_names_keeps_turn() {
+    let mut combat = goblin_fight();
+    combat.start();
+    combat.next_turn();
+    let goblin = combat.current().unwrap().id;
+    combat.add_group("Goblin", 2, 15, 7);
+    assert_eq!(combat.current_turn(), Some(Turn::Combatant(3)));
+    assert_eq!(combat.current().unwrap().id, goblin);
+}
+
+#[test]
+fn add_ahead_of_group_keeps_group_turn() {
+    let mut combat = goblin_fight();
+    combat.set_group_initiative(true);
+    combat.start();
+    combat.next_turn();
+    assert_eq!(combat.current_turn(), Some(Turn::Group(1)));
+    let boss = Combatant::new("Goblin Boss", 20, 21).with_group("Goblin");
+    combat.add(boss);
+    assert_eq!(combat.current_turn(), Some(Turn::Group(0)));
+    assert_eq!(combat.members(Turn::Group(0)), vec![0, 2, 3, 4]);
+    combat.next_turn();
+    assert_eq!(combat.current().unwrap().name, "Fighter");
+}
+
+#[test]
+fn damage_group_skips_fallen() {
+    let mut combat = goblin_fight();
+    combat.start();
+    combat.damage(2, 7, None);
+    let logged = combat.log().entries().len();
+    combat.damage_group("Goblin", 5, Some("Wizard"));
+    let hp: Vec<i64> = combat.combatants().iter().map(|c| c.hp).collect();
+    assert_eq!(hp, vec![30, 2, 0, 2, 14]);
+    assert_eq!(combat.log().entries().len(), logged + 2);
+}
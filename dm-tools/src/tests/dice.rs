@@ -0,0 +1,56 @@
+use crate::dice::*;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+#[test]
+fn dice_parse() {
+    let table = [
+        ("1d4+1", Dice::new(1, 4, 1)),
+        ("2d6", Dice::new(2, 6, 0)),
+        ("d20", Dice::new(1, 20, 0)),
+        ("3d8 - 2", Dice::new(3, 8, -2)),
+        ("4", Dice::flat(4)),
+        ("-1", Dice::flat(-1)),
+    ];
+    for (text, dice) in table.iter() {
+        assert_eq!(text.parse::<Dice>().as_ref(), Ok(dice), "{}", text);
+    }
+}
+
+#[test]
+fn dice_parse_invalid() {
+    for text in ["", "d", "1d", "1d0", "1d4+", "2+3", "wolves"].iter() {
+        assert!(text.parse::<Dice>().is_err(), "{}", text);
+    }
+}
+
+#[test]
+fn dice_display_round_trip() {
+    for text in ["1d4+1", "2d6", "3d8-2", "4"].iter() {
+        assert_eq!(text.parse::<Dice>().unwrap().to_string(), *text);
+    }
+}
+
+#[test]
+fn dice_bounds() {
+    let dice = Dice::new(2, 6, 1);
+    assert_eq!(dice.min(), 3);
+    assert_eq!(dice.max(), 13);
+    assert_eq!(dice.average(), 8);
+}
+
+#[test]
+fn dice_roll_in_bounds() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let dice = Dice::new(1, 4, 1);
+    for _ in 0..100 {
+        let roll = dice.roll(&mut rng);
+        assert!(roll >= dice.min() && roll <= dice.max());
+    }
+}
+
+#[test]
+fn dice_roll_flat() {
+    let mut rng = StdRng::seed_from_u64(0);
+    assert_eq!(Dice::flat(5).roll(&mut rng), 5);
+}
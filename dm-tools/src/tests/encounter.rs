@@ -0,0 +1,224 @@
+use crate::encounter::ChallengeRating::*;
+use crate::encounter::Difficulty::*;
+use crate::encounter::*;
+
+fn goblins(count: usize) -> Monster {
+    Monster::new("Goblin", Quarter, count)
+}
+
+#[test]
+fn thresholds_by_level() {
+    let table = [
+        (1, [25, 50, 75, 100]),
+        (2, [50, 100, 150, 200]),
+        (3, [75, 150, 225, 400]),
+        (4, [125, 250, 375, 500]),
+        (5, [250, 500, 750, 1100]),
+        (6, [300, 600, 900, 1400]),
+        (7, [350, 750, 1100, 1700]),
+        (8, [450, 900, 1400, 2100]),
+        (9, [550, 1100, 1600, 2400]),
+        (10, [600, 1200, 1900, 2800]),
+        (11, [800, 1600, 2400, 3600]),
+        (12, [1000, 2000, 3000, 4500]),
+        (13, [1100, 2200, 3400, 5100]),
+        (14, [1250, 2500, 3800, 5700]),
+        (15, [1400, 2800, 4300, 6400]),
+        (16, [1600, 3200, 4800, 7200]),
+        (17, [2000, 3900, 5900, 8800]),
+        (18, [2100, 4200, 6300, 9500]),
+        (19, [2400, 4900, 7300, 10900]),
+        (20, [2800, 5700, 8500, 12700]),
+    ];
+    for (level, expected) in table.iter() {
+        let actual = [
+            Easy.threshold(*level),
+            Medium.threshold(*level),
+            Hard.threshold(*level),
+            Deadly.threshold(*level),
+        ];
+        assert_eq!(&actual, expected, "level {}", level);
+        assert_eq!(Trivial.threshold(*level), 0);
+    }
+}
+
+#[test]
+fn thresholds_clamp_level() {
+    assert_eq!(Easy.threshold(0), Easy.threshold(1));
+    assert_eq!(Deadly.threshold(25), Deadly.threshold(20));
+}
+
+#[test]
+fn challenge_rating_xp() {
+    let table = [
+        ("0", 10),
+        ("1/8", 25),
+        ("1/4", 50),
+        ("1/2", 100),
+        ("1", 200),
+        ("2", 450),
+        ("3", 700),
+        ("4", 1100),
+        ("5", 1800),
+        ("6", 2300),
+        ("7", 2900),
+        ("8", 3900),
+        ("9", 5000),
+        ("10", 5900),
+        ("11", 7200),
+        ("12", 8400),
+        ("13", 10000),
+        ("14", 11500),
+        ("15", 13000),
+        ("16", 15000),
+        ("17", 18000),
+        ("18", 20000),
+        ("19", 22000),
+        ("20", 25000),
+        ("21", 33000),
+        ("22", 41000),
+        ("23", 50000),
+        ("24", 62000),
+        ("25", 75000),
+        ("26", 90000),
+        ("27", 105000),
+        ("28", 120000),
+        ("29", 135000),
+        ("30", 155000),
+    ];
+    for (cr, xp) in table.iter() {
+        let rating = cr.parse::<ChallengeRating>().unwrap();
+        assert_eq!(rating.xp(), *xp, "CR {}", cr);
+    }
+}
+
+#[test]
+fn challenge_rating_invalid() {
+    for cr in ["", "31", "-1", "1/3", "one"].iter() {
+        assert_eq!(
+            cr.parse::<ChallengeRating>(),
+            Err(InvalidChallengeRating),
+            "CR {:?}",
+            cr
+        );
+    }
+}
+
+#[test]
+fn challenge_rating_ordered() {
+    assert!(Zero < Eighth);
+    assert!(Half < Whole(1));
+    assert!(Whole(1) < Whole(10));
+}
+
+#[test]
+fn multiplier_by_monster_count() {
+    // (monsters, party of 1-2, party of 3-5, party of 6+)
+    let table = [
+        (1, 1.5, 1.0, 0.5),
+        (2, 2.0, 1.5, 1.0),
+        (3, 2.5, 2.0, 1.5),
+        (6, 2.5, 2.0, 1.5),
+        (7, 3.0, 2.5, 2.0),
+        (10, 3.0, 2.5, 2.0),
+        (11, 4.0, 3.0, 2.5),
+        (14, 4.0, 3.0, 2.5),
+        (15, 5.0, 4.0, 3.0),
+        (30, 5.0, 4.0, 3.0),
+    ];
+    for (count, small, normal, large) in table.iter() {
+        let monsters = vec![goblins(*count)];
+        for (party, expected) in
+            [(1, small), (2, small), (3, normal), (5, normal), (6, large)].iter()
+        {
+            let encounter = Encounter::new(vec![1; *party], monsters.clone());
+            assert_eq!(
+                encounter.multiplier(),
+                **expected,
+                "{} monsters, party of {}",
+                count,
+                party
+            );
+        }
+    }
+}
+
+#[test]
+fn multiplier_counts_every_monster() {
+    let encounter = Encounter::new(
+        vec![1, 1, 1, 1],
+        vec![goblins(2), Monster::new("Wolf", Quarter, 1)],
+    );
+    assert_eq!(encounter.monster_count(), 3);
+    assert_eq!(encounter.multiplier(), 2.0);
+}
+
+#[test]
+fn adjusted_xp() {
+    let encounter = Encounter::new(vec![3, 3, 3, 3], vec![goblins(3)]);
+    assert_eq!(encounter.base_xp(), 150);
+    assert_eq!(encounter.adjusted_xp(), 300);
+}
+
+#[test]
+fn adjusted_xp_rounds_down() {
+    let encounter = Encounter::new(vec![1; 6], vec![Monster::new("Rat", Eighth, 1)]);
+    assert_eq!(encounter.adjusted_xp(), 12);
+}
+
+#[test]
+fn difficulty_by_adjusted_xp() {
+    // Party of four 3rd level characters: 300 / 600 / 900 / 1600.
+    let party = vec![3, 3, 3, 3];
+    let table = [
+        (vec![goblins(1)], Trivial),
+        (vec![goblins(3)], Easy),
+        (vec![Monster::new("Orc", Half, 2)], Easy),
+        (vec![Monster::new("Orc", Half, 3)], Medium),
+        (vec![Monster::new("Orc", Half, 5)], Hard),
+        (vec![Monster::new("Orc", Half, 7)], Deadly),
+        (vec![Monster::new("Ogre", Whole(2), 1)], Easy),
+        (vec![Monster::new("Ogre", Whole(2), 1), goblins(4)], Hard),
+    ];
+    for (monsters, difficulty) in table.iter() {
+        let encounter = Encounter::new(party.clone(), monsters.clone());
+        assert_eq!(
+            encounter.difficulty(),
+            *difficulty,
+            "{:?} ({} adjusted XP)",
+            monsters,
+            encounter.adjusted_xp()
+        );
+    }
+}
+
+#[test]
+fn difficulty_mixed_levels() {
+    let encounter = Encounter::new(vec![1, 5], vec![Monster::new("Ogre", Whole(2), 1)]);
+    assert_eq!(encounter.threshold(Medium), 550);
+    assert_eq!(encounter.adjusted_xp(), 675);
+    assert_eq!(encounter.difficulty(), Medium);
+}
+
+#[test]
+fn difficulty_empty() {
+    assert_eq!(Encounter::default().difficulty(), Trivial);
+    assert_eq!(Encounter::new(vec![1], vec![]).difficulty(), Trivial);
+    assert_eq!(
+        Encounter::new(vec![], vec![goblins(1)]).difficulty(),
+        Trivial
+    );
+}
+
+#[test]
+fn xp_per_character() {
+    let table = [
+        (vec![3, 3, 3, 3], vec![goblins(3)], 37),
+        (vec![1, 2], vec![Monster::new("Ogre", Whole(2), 2)], 450),
+        (vec![], vec![goblins(3)], 0),
+    ];
+    for (party, monsters, xp) in table.iter() {
+        let encounter = Encounter::new(party.clone(), monsters.clone());
+        assert_eq!(encounter.xp_per_character(), *xp);
+    }
+}
@@ -0,0 +1,90 @@
+use crate::character::Character;
+use crate::coin::format_coins;
+use crate::coin::Coin::*;
+use crate::inventory::Encumbrance::*;
+use crate::inventory::EncumbranceRule::*;
+use crate::inventory::*;
+
+fn pack() -> Inventory {
+    let mut inventory = Inventory::new();
+    inventory.push(Item::new("Longsword", 1, 3.0).with_value(15, Gold));
+    let mut backpack = Item::new("Backpack", 1, 5.0).with_value(2, Gold);
+    backpack.push(Item::new("Rations", 5, 2.0).with_value(5, Silver));
+    backpack.push(Item::new("Torch", 10, 1.0).with_value(1, Copper));
+    inventory.push(backpack);
+    inventory
+}
+
+#[test]
+fn weight_and_value() {
+    let inventory = pack();
+    assert_eq!(inventory.weight(), 28.0);
+    assert_eq!(format_coins(&inventory.value()), "17 gp 25 sp 10 cp");
+    assert_eq!(inventory.value_in_copper(), 1960);
+}
+
+#[test]
+fn nested_containers() {
+    let mut inventory = pack();
+    inventory
+        .push_into("Backpack", Item::new("Pouch", 1, 1.0))
+        .unwrap();
+    inventory
+        .push_into("Pouch", Item::new("Chalk", 2, 0.0).with_value(1, Copper))
+        .unwrap();
+    assert_eq!(inventory.weight(), 29.0);
+    assert_eq!(inventory.value_in_copper(), 1962);
+    assert!(inventory
+        .push_into("Chest", Item::new("Gem", 1, 0.0))
+        .is_err());
+
+    let pouch = inventory.remove("Pouch").unwrap();
+    assert_eq!(pouch.contents.len(), 1);
+    assert_eq!(inventory.weight(), 28.0);
+}
+
+#[test]
+fn bag_of_holding_ignores_contents() {
+    let mut inventory = Inventory::new();
+    let mut bag = Item::new("Bag of Holding", 1, 15.0).with_weightless_contents();
+    bag.push(Item::new("Anvil", 1, 500.0));
+    inventory.push(bag);
+    assert_eq!(inventory.weight(), 15.0);
+}
+
+#[test]
+fn standard_encumbrance() {
+    assert_eq!(encumbrance(150.0, 10, Standard), Unencumbered);
+    assert_eq!(encumbrance(151.0, 10, Standard), OverCapacity);
+}
+
+#[test]
+fn variant_encumbrance() {
+    assert_eq!(encumbrance(50.0, 10, Variant), Unencumbered);
+    assert_eq!(encumbrance(51.0, 10, Variant), Encumbered);
+    assert_eq!(encumbrance(101.0, 10, Variant), HeavilyEncumbered);
+    assert_eq!(encumbrance(151.0, 10, Variant), OverCapacity);
+    assert_eq!(HeavilyEncumbered.speed_penalty(), 20);
+}
+
+#[test]
+fn encumbrance_speed() {
+    assert_eq!(Unencumbered.speed(30), 30);
+    assert_eq!(Encumbered.speed(30), 20);
+    assert_eq!(HeavilyEncumbered.speed(25), 5);
+    assert_eq!(HeavilyEncumbered.speed(15), 0);
+    assert_eq!(OverCapacity.speed(30), 5);
+    assert_eq!(OverCapacity.speed(0), 0);
+    assert_eq!(OverCapacity.speed_cap(), Some(OVER_CAPACITY_SPEED));
+}
+
+#[test]
+fn character_encumbrance() {
+    let mut character = Character::new("Tordek", "Fighter", 1);
+    character.abilities.strength = 8;
+    character.inventory = pack();
+    character.inventory.push(Item::new("Chain shirt", 1, 20.0));
+    assert_eq!(character.carrying_capacity(), 120.0);
+    assert_eq!(character.encumbrance(Standard), Unencumbered);
+    assert_eq!(character.encumbrance(Variant), Encumbered);
+}
@@ -0,0 +1,44 @@
+use crate::keys::*;
+
+#[test]
+fn default_steps() {
+    let keys = KeyBindings::default();
+    assert_eq!(keys.step("Return"), Some(1));
+    assert_eq!(keys.step("7"), Some(7));
+    assert_eq!(keys.step("KP_0"), Some(10));
+    assert_eq!(keys.step("a"), None);
+}
+
+#[test]
+fn default_shortcuts() {
+    let keys = KeyBindings::default();
+    assert_eq!(keys.keys_for(Action::Help).collect::<Vec<_>>(), vec!["F1"]);
+    assert_eq!(
+        keys.keys_for(Action::Advance(600)).collect::<Vec<_>>(),
+        vec!["F6"]
+    );
+    assert_eq!(keys.keys_for(Action::Tab(3)).count(), 1);
+}
+
+#[test]
+fn partial_file_keeps_defaults() {
+    let keys = KeyBindings::from_json(
+        r#"{ "shortcuts": [{ "keys": "<Primary>h", "action": { "Advance": 3600 } }] }"#,
+    )
+    .unwrap();
+    assert_eq!(keys.shortcuts.len(), 1);
+    assert_eq!(keys.step("0"), Some(10));
+}
+
+#[test]
+fn describe_actions() {
+    assert_eq!(Action::Advance(6).to_string(), "Advance 1 round");
+    assert_eq!(Action::Advance(600).to_string(), "Advance 10 min");
+    assert_eq!(Action::Advance(5400).to_string(), "Advance 1 h 30 min");
+    assert_eq!(Action::Tab(0).to_string(), "Go to tab 1");
+}
+
+#[test]
+fn invalid_file() {
+    assert!(KeyBindings::from_json("{ \"shortcuts\": 3 }").is_err());
+}
@@ -0,0 +1,173 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::character::{Ability, Character};
+use crate::level::*;
+use crate::resource::Recovery;
+use crate::table::LoadError;
+
+const SRD: &str = include_str!("../../data/classes/srd.json");
+
+fn rng() -> StdRng {
+    StdRng::seed_from_u64(5)
+}
+
+fn wizard(level: u8) -> Character {
+    let mut character = Character::new("Mialee", "Wizard", level);
+    character.hit_die = 6;
+    character.abilities.set(Ability::Constitution, 14);
+    character.max_hp = 14;
+    character.hp = 14;
+    character
+}
+
+#[test]
+fn xp_table() {
+    assert_eq!(level_for_xp(0), 1);
+    assert_eq!(level_for_xp(299), 1);
+    assert_eq!(level_for_xp(300), 2);
+    assert_eq!(level_for_xp(6500), 5);
+    assert_eq!(level_for_xp(1_000_000), 20);
+    assert_eq!(xp_for_next_level(1), Some(300));
+    assert_eq!(xp_for_next_level(20), None);
+}
+
+#[test]
+fn slot_names() {
+    assert_eq!(slot_name(1), "1st level slots");
+    assert_eq!(slot_name(3), "3rd level slots");
+    assert_eq!(slot_name(9), "9th level slots");
+}
+
+#[test]
+fn load_progressions() {
+    let progressions = Progressions::from_json(SRD).unwrap();
+    let wizard = progressions.find("wizard").unwrap();
+    assert_eq!(wizard.hit_die, 6);
+    assert_eq!(wizard.slots(1), &[2]);
+    assert_eq!(wizard.slots(5), &[4, 3, 2]);
+    assert_eq!(wizard.slots(20), &[4, 3, 3, 3, 3, 2, 2, 1, 1]);
+    assert!(progressions.find("Fighter").unwrap().slots(5).is_empty());
+    assert!(progressions.find("Paladin").unwrap().slots(1).is_empty());
+    assert!(progressions.find("Bard").is_none());
+}
+
+#[test]
+fn srd_progressions() {
+    assert_eq!(Progressions::srd(), Progressions::from_json(SRD).unwrap());
+}
+
+#[test]
+fn load_missing_file() {
+    match Progressions::from_file("does/not/exist.json") {
+        Err(LoadError::Io(_)) => {}
+        other => panic!("{:?}", other),
+    }
+}
+
+#[test]
+fn pending_levels_from_xp() {
+    let mut character = wizard(1);
+    character.award_xp(250);
+    assert_eq!(character.pending_levels(), 0);
+    character.award_xp(700);
+    assert_eq!(character.pending_levels(), 2);
+}
+
+#[test]
+fn pending_levels_from_milestones() {
+    let mut character = wizard(3);
+    character.award_milestone("Cleared the goblin caves");
+    assert_eq!(character.milestones, vec!["Cleared the goblin caves"]);
+    assert_eq!(character.pending_levels(), 1);
+    character
+        .level_up(HitPoints::Average, None, &mut rng())
+        .unwrap();
+    assert_eq!(character.level, 4);
+    assert_eq!(character.pending_levels(), 0);
+}
+
+#[test]
+fn pending_levels_past_twenty() {
+    let mut character = wizard(20);
+    character.level = 25;
+    character.award_milestone("Slew the tarrasque");
+    assert_eq!(character.pending_levels(), 0);
+}
+
+#[test]
+fn no_level_up_pending() {
+    let mut character = wizard(1);
+    assert_eq!(
+        character.level_up(HitPoints::Average, None, &mut rng()),
+        None
+    );
+    assert_eq!(character.level, 1);
+}
+
+#[test]
+fn level_up_average() {
+    let progressions = Progressions::from_json(SRD).unwrap();
+    let mut character = wizard(4);
+    character.award_xp(6500);
+    let level_up = character
+        .level_up(HitPoints::Average, progressions.find("Wizard"), &mut rng())
+        .unwrap();
+    assert_eq!(
+        level_up,
+        LevelUp {
+            level: 5,
+            hp: 6,
+            proficiency_bonus: 3,
+            slots: vec![(3, 2)],
+        }
+    );
+    assert_eq!(character.max_hp, 20);
+    assert_eq!(character.hit_dice, 5);
+
+    let slots = character.resources.get("3rd level slots").unwrap();
+    assert_eq!((slots.current, slots.max), (2, 2));
+    assert_eq!(slots.recovery, Recovery::LongRest);
+    assert_eq!(character.resources.get("1st level slots").unwrap().max, 4);
+}
+
+#[test]
+fn level_up_uses_class_hit_die() {
+    let progressions = Progressions::from_json(SRD).unwrap();
+    let mut character = Character::new("Tordek", "Fighter", 1);
+    character.award_xp(300);
+    let level_up = character
+        .level_up(HitPoints::Average, progressions.find("Fighter"), &mut rng())
+        .unwrap();
+    assert_eq!(level_up.hp, 6);
+}
+
+#[test]
+fn level_up_rolled() {
+    let mut character = wizard(1);
+    character.award_xp(300);
+    let level_up = character
+        .level_up(HitPoints::Rolled, None, &mut rng())
+        .unwrap();
+    assert!((3..=8).contains(&level_up.hp));
+    assert_eq!(character.max_hp, 14 + level_up.hp);
+    assert!(level_up.slots.is_empty());
+}
+
+#[test]
+fn level_up_keeps_spent_slots() {
+    let progressions = Progressions::from_json(SRD).unwrap();
+    let mut character = wizard(1);
+    character.award_xp(900);
+    let wizard = progressions.find("Wizard");
+    character.level_up(HitPoints::Average, wizard, &mut rng());
+    character.resources.spend("1st level slots", 3);
+    let level_up = character
+        .level_up(HitPoints::Average, wizard, &mut rng())
+        .unwrap();
+    assert_eq!(level_up.slots, vec![(1, 1), (2, 2)]);
+    assert_eq!(
+        character.resources.get("1st level slots").unwrap().current,
+        1
+    );
+}
@@ -0,0 +1,65 @@
+use crate::mob::*;
+
+#[test]
+fn attackers_per_hit_table() {
+    // (roll needed, attackers per hit)
+    let table = [
+        (-3, 1),
+        (1, 1),
+        (5, 1),
+        (6, 2),
+        (12, 2),
+        (13, 3),
+        (14, 3),
+        (15, 4),
+        (16, 4),
+        (17, 5),
+        (18, 5),
+        (19, 10),
+        (20, 20),
+        (25, 20),
+    ];
+    for (needed, attackers) in table.iter() {
+        assert_eq!(
+            attackers_per_hit(*needed + 4, 4),
+            *attackers,
+            "d20 {}",
+            needed
+        );
+    }
+}
+
+#[test]
+fn table_hits_rounds_down() {
+    // Goblins (+4) against AC 15 need an 11: two attackers per hit.
+    assert_eq!(table_hits(20, 15, 4), 10);
+    assert_eq!(table_hits(7, 15, 4), 3);
+    assert_eq!(table_hits(1, 15, 4), 0);
+}
+
+#[test]
+fn hit_chance_bounds() {
+    assert_eq!(hit_chance(15, 4), 0.5);
+    assert_eq!(hit_chance(10, 15), 0.95);
+    assert_eq!(hit_chance(30, 0), 0.05);
+}
+
+#[test]
+fn expected_hits_by_chance() {
+    assert_eq!(expected_hits(20, 15, 4), 10.0);
+    assert_eq!(expected_hits(20, 30, 0), 1.0);
+}
+
+#[test]
+fn distribution_sums_to_one() {
+    let distribution = hit_distribution(10, 15, 4);
+    assert_eq!(distribution.len(), 11);
+    let total: f64 = distribution.iter().sum();
+    assert!((total - 1.0).abs() < 1e-9);
+    assert!((distribution[5] - 252.0 / 1024.0).abs() < 1e-9);
+}
+
+#[test]
+fn distribution_no_attackers() {
+    assert_eq!(hit_distribution(0, 15, 4), vec![1.0]);
+}
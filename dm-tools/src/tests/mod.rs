@@ -1,2 +1,17 @@
+mod calendar;
+mod character;
+mod coin;
+mod combat;
+mod dice;
+mod encounter;
+mod inventory;
+mod keys;
+mod level;
+mod mob;
+mod preset;
+mod resource;
+mod table;
 mod time;
+mod treasure;
 mod unit;
+mod world;
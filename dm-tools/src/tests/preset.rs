@@ -0,0 +1,44 @@
+use crate::preset::*;
+use crate::time::{Time, UnitTime};
+
+#[test]
+fn builtin_presets() {
+    let presets = Preset::builtin();
+    let seconds: Vec<i64> = presets.iter().map(|p| p.duration.value).collect();
+    assert_eq!(seconds, vec![6, 60, 600, 3600, 3600, 28800, 86400, 28800]);
+    assert_eq!(presets[4].name, "Short rest");
+}
+
+#[test]
+fn custom_presets_follow_builtin() {
+    let mut presets = Presets::default();
+    presets.add(Preset::new("Watch", Time::from(2, UnitTime::Hour)));
+    presets.add(Preset::new("Ritual", Time::from(10, UnitTime::Minute)));
+    let all = presets.all();
+    assert_eq!(all.len(), Preset::builtin().len() + 2);
+    assert_eq!(all.last().unwrap().name, "Ritual");
+}
+
+#[test]
+fn add_replaces_same_name() {
+    let mut presets = Presets::default();
+    presets.add(Preset::new("Watch", Time::from(2, UnitTime::Hour)));
+    presets.add(Preset::new("Watch", Time::from(3, UnitTime::Hour)));
+    assert_eq!(presets.custom.len(), 1);
+    assert_eq!(presets.custom[0].duration, Time::from(3, UnitTime::Hour));
+
+    assert!(presets.remove("Watch").is_some());
+    assert!(presets.remove("Watch").is_none());
+}
+
+#[test]
+fn presets_round_trip() {
+    let mut presets = Presets::default();
+    presets.add(Preset::new("Watch", Time::from(2, UnitTime::Hour)));
+    let path = std::env::temp_dir().join("dm-toolkit-presets-test.json");
+    presets.save(&path).unwrap();
+    assert_eq!(Presets::from_file(&path).unwrap(), presets);
+    std::fs::remove_file(path).unwrap();
+
+    assert!(Presets::from_json("{ \"custom\": 3 }").is_err());
+}
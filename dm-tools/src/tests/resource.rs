@@ -0,0 +1,103 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::character::Character;
+use crate::resource::*;
+use crate::time::UnitTime::*;
+use crate::time::*;
+use crate::world::World;
+
+fn wizard() -> Character {
+    let mut character = Character::new("Mialee", "Wizard", 3);
+    let resources = &mut character.resources;
+    resources.push(Resource::new("1st level slots", 4, Recovery::LongRest));
+    resources.push(Resource::new("Arcane Recovery", 1, Recovery::LongRest));
+    resources.push(Resource::new("Ki", 3, Recovery::ShortRest));
+    resources.push(Resource::new("Wand charges", 7, Recovery::Dawn));
+    resources.push(Resource::new("Breath", 1, Recovery::Recharge(5)));
+    character
+}
+
+fn rng() -> StdRng {
+    StdRng::seed_from_u64(1)
+}
+
+fn current(world: &World, name: &str) -> u32 {
+    world.party[0].resources.get(name).unwrap().current
+}
+
+#[test]
+fn spend() {
+    let mut resource = Resource::new("Ki", 3, Recovery::ShortRest);
+    assert!(resource.spend(2));
+    assert!(!resource.spend(2));
+    assert_eq!(resource.current, 1);
+    resource.restore();
+    assert_eq!(resource.current, 3);
+}
+
+#[test]
+fn recovery_display() {
+    assert_eq!(Recovery::Recharge(5).to_string(), "Recharge 5-6");
+    assert_eq!(Recovery::Recharge(6).to_string(), "Recharge 6");
+    assert_eq!(Recovery::ShortRest.to_string(), "Short rest");
+}
+
+#[test]
+fn roll_recharge() {
+    let mut rng = StdRng::seed_from_u64(3);
+    let mut resource = Resource::new("Breath", 1, Recovery::Recharge(5));
+    resource.spend(1);
+    let mut rolls = 0;
+    while !resource.roll_recharge(&mut rng) {
+        rolls += 1;
+        assert!(rolls < 100);
+    }
+    assert_eq!(resource.current, 1);
+
+    let mut ki = Resource::new("Ki", 3, Recovery::ShortRest);
+    ki.spend(1);
+    assert!(!ki.roll_recharge(&mut rng));
+}
+
+#[test]
+fn short_rest() {
+    let mut world = World::new();
+    world.party.push(wizard());
+    for character in world.party.iter_mut() {
+        for resource in ["1st level slots", "Ki", "Breath"].iter() {
+            assert!(character.resources.spend(resource, 1));
+        }
+    }
+    world.rest(Rest::Short, &[], &mut rng());
+    assert_eq!(world.time(), &Time::from(1, Hour));
+    assert_eq!(current(&world, "1st level slots"), 3);
+    assert_eq!(current(&world, "Ki"), 3);
+    assert_eq!(current(&world, "Breath"), 1);
+}
+
+#[test]
+fn long_rest() {
+    let mut world = World::new();
+    world.party.push(wizard());
+    world.party[0].resources.spend("1st level slots", 4);
+    world.party[0].resources.spend("Arcane Recovery", 1);
+    world.rest(Rest::Long, &[], &mut rng());
+    assert_eq!(world.time(), &Time::from(8, Hour));
+    assert_eq!(current(&world, "1st level slots"), 4);
+    assert_eq!(current(&world, "Arcane Recovery"), 1);
+}
+
+#[test]
+fn dawn() {
+    let mut world = World::new();
+    world.party.push(wizard());
+    world.party[0].resources.spend("Wand charges", 5);
+
+    // Midnight to 05:00, the sun isn't up yet.
+    world.advance(&Time::from(5, Hour));
+    assert_eq!(current(&world, "Wand charges"), 2);
+
+    world.rest(Rest::Short, &[], &mut rng());
+    assert_eq!(current(&world, "Wand charges"), 7);
+}
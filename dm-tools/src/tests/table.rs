@@ -0,0 +1,129 @@
+use crate::encounter::ChallengeRating::*;
+use crate::encounter::Difficulty::*;
+use crate::table::*;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+const FOREST: &str = include_str!("../../data/encounters/forest.json");
+
+fn table(entries: &str) -> EncounterTable {
+    let json = format!(
+        r#"{{"terrain": "Plains", "min_cr": "0", "max_cr": "4", "entries": {}}}"#,
+        entries
+    );
+    serde_json::from_str(&json).unwrap()
+}
+
+#[test]
+fn load_tables() {
+    let tables = EncounterTables::from_json(FOREST).unwrap();
+    assert_eq!(tables.tables().len(), 2);
+    assert_eq!(tables.terrains(), vec!["Forest"]);
+    let entry = &tables.tables()[0].entries[0];
+    assert_eq!(entry.name, "Wolf");
+    assert_eq!(entry.cr, Quarter);
+    assert_eq!(entry.quantity.to_string(), "1d4+1");
+}
+
+#[test]
+fn load_invalid() {
+    let json = r#"[{"terrain": "Forest", "min_cr": "1/3", "max_cr": "4", "entries": []}]"#;
+    match EncounterTables::from_json(json) {
+        Err(LoadError::Parse(_)) => {}
+        other => panic!("{:?}", other),
+    }
+}
+
+#[test]
+fn load_missing_file() {
+    match EncounterTables::from_file("does/not/exist.json") {
+        Err(LoadError::Io(_)) => {}
+        other => panic!("{:?}", other),
+    }
+}
+
+#[test]
+fn find_by_xp_budget() {
+    let tables = EncounterTables::from_json(FOREST).unwrap();
+    // 800 XP for a medium encounter, within CR 0 to 4.
+    let low = tables.find("forest", &[3, 3, 4, 4], Medium);
+    assert_eq!(low.len(), 2);
+    assert_eq!(low[0].max_cr, Whole(4));
+    // 2400 XP, within CR 5 to 10.
+    let high = tables.find("Forest", &[7, 7, 8], Medium);
+    assert_eq!(high[0].min_cr, Whole(5));
+    // The same party facing a trivial fight gets the weaker table.
+    assert_eq!(tables.find("Forest", &[7, 7, 8], Easy)[0].max_cr, Whole(4));
+    assert!(tables.find("Desert", &[3], Medium).is_empty());
+}
+
+#[test]
+fn find_between_bands() {
+    let tables = EncounterTables::from_json(FOREST).unwrap();
+    // 1500 XP falls between CR 4 (1100) and CR 5 (1800), the closer wins.
+    let found = tables.find("Forest", &[4, 4, 5, 5], Medium);
+    assert_eq!(found[0].min_cr, Whole(5));
+}
+
+#[test]
+fn bundled_tables() {
+    assert_eq!(EncounterTables::bundled().terrains(), vec!["Forest"]);
+}
+
+#[test]
+fn roll_respects_weights() {
+    let table = table(
+        r#"[
+            {"weight": 0, "quantity": "1", "name": "Never", "cr": "1"},
+            {"weight": 1, "quantity": "1d4+1", "name": "Wolf", "cr": "1/4"}
+        ]"#,
+    );
+    let mut rng = StdRng::seed_from_u64(1);
+    for _ in 0..50 {
+        let monster = table.roll(&mut rng).unwrap();
+        assert_eq!(monster.name, "Wolf");
+        assert!(monster.count >= 2 && monster.count <= 5);
+    }
+}
+
+#[test]
+fn roll_empty_table() {
+    let mut rng = StdRng::seed_from_u64(1);
+    assert_eq!(table("[]").roll(&mut rng), None);
+}
+
+#[test]
+fn generate_fits_difficulty() {
+    let tables = EncounterTables::from_json(FOREST).unwrap();
+    let party = [3, 3, 3, 3];
+    let mut rng = StdRng::seed_from_u64(2);
+    for difficulty in [Easy, Medium, Hard, Deadly].iter() {
+        for _ in 0..20 {
+            let encounter = tables
+                .generate("Forest", &party, *difficulty, &mut rng)
+                .unwrap();
+            assert_eq!(encounter.difficulty(), *difficulty);
+            assert_eq!(encounter.party, party.to_vec());
+        }
+    }
+}
+
+#[test]
+fn generate_skips_entries_that_cannot_fit() {
+    let table = table(
+        r#"[
+            {"weight": 100, "quantity": "1", "name": "Rat", "cr": "0"},
+            {"weight": 1, "quantity": "1d4+1", "name": "Wolf", "cr": "1/4"}
+        ]"#,
+    );
+    let mut rng = StdRng::seed_from_u64(3);
+    let encounter = table.generate(&[1, 1, 1, 1], Hard, &mut rng).unwrap();
+    assert_eq!(encounter.monsters[0].name, "Wolf");
+}
+
+#[test]
+fn generate_impossible_difficulty() {
+    let table = table(r#"[{"weight": 1, "quantity": "1", "name": "Rat", "cr": "0"}]"#);
+    let mut rng = StdRng::seed_from_u64(4);
+    assert_eq!(table.generate(&[5, 5, 5, 5], Deadly, &mut rng), None);
+}
@@ -26,6 +26,8 @@ fn schedule_not_active_before_time() {
         start: start,
         end: end,
         id: 1,
+        name: "Event 1".to_string(),
+        category: Category::Other,
     });
     assert_eq!(
         schedule.active_events().collect::<Vec<_>>(),
@@ -42,6 +44,8 @@ fn schedule_active_in_time() {
         start: start,
         end: end,
         id: 1,
+        name: "Event 1".to_string(),
+        category: Category::Other,
     };
     schedule.push(event.clone());
     schedule.time.value += Second.value();
@@ -57,6 +61,8 @@ fn schedule_not_active_after_time() {
         start: start,
         end: end,
         id: 1,
+        name: "Event 1".to_string(),
+        category: Category::Other,
     };
     schedule.push(event.clone());
     schedule.time.value += Second.value() * 3;
@@ -73,11 +79,15 @@ fn events_by_id() {
         start: Time::from(1, Second),
         end: Time::from(2, Second),
         id: 1,
+        name: "Event 1".to_string(),
+        category: Category::Other,
     };
     let event2 = Event {
         start: Time::from(1, Second),
         end: Time::from(2, Second),
         id: 2,
+        name: "Event 2".to_string(),
+        category: Category::Other,
     };
     schedule.push(event.clone());
     schedule.push(event2.clone());
@@ -91,11 +101,15 @@ fn active_events_by_id() {
         start: Time::from(3, Second),
         end: Time::from(4, Second),
         id: 2,
+        name: "Event 2".to_string(),
+        category: Category::Other,
     };
     let event2 = Event {
         start: Time::from(1, Second),
         end: Time::from(2, Second),
         id: 2,
+        name: "Event 2".to_string(),
+        category: Category::Other,
     };
     schedule.push(event.clone());
     schedule.push(event2.clone());
@@ -105,3 +119,178 @@ fn active_events_by_id() {
         vec![&event2]
     );
 }
+
+#[test]
+fn time_display() {
+    let time = Time {
+        value: Time::from(2, Day).value + Time::from(13, Hour).value + 65,
+    };
+    assert_eq!(time.to_string(), "Day 3, 13:01:05");
+}
+
+#[test]
+fn dawns_until() {
+    let midnight = Time::new();
+    assert_eq!(midnight.dawns_until(&Time::from(5, Hour)), 0);
+    assert_eq!(midnight.dawns_until(&Time::from(6, Hour)), 1);
+    assert_eq!(midnight.dawns_until(&Time::from(3, Day)), 3);
+    assert_eq!(Time::from(6, Hour).dawns_until(&Time::from(7, Hour)), 0);
+    assert_eq!(Time::from(1, Day).dawns_until(&Time::new()), 0);
+}
+
+#[test]
+fn schedule_advance() {
+    let mut schedule = Scheduler::new();
+    let event = Event {
+        start: Time::from(1, Minute),
+        end: Time::from(2, Minute),
+        id: 1,
+        name: "Event 1".to_string(),
+        category: Category::Other,
+    };
+    schedule.push(event.clone());
+    assert_eq!(schedule.advance(&Time::from(30, Second)), vec![]);
+    assert_eq!(schedule.advance(&Time::from(30, Second)), vec![event]);
+    assert_eq!(schedule.time, Time::from(1, Minute));
+    assert_eq!(schedule.advance(&Time::from(1, Hour)), vec![]);
+}
+
+#[test]
+fn time_units() {
+    let duration = Time::from(90, UnitTime::Minute);
+    assert_eq!(duration.units().unwrap().to_string(), "1 h 30 min");
+}
+
+#[test]
+fn schedule_assigns_new_ids() {
+    let mut schedule = Scheduler::new();
+    let first = schedule.schedule(
+        "Festival",
+        Category::Story,
+        Time::from(1, Day),
+        Time::from(2, Day),
+    );
+    let second = schedule.schedule(
+        "Eclipse",
+        Category::Story,
+        Time::from(3, Day),
+        Time::from(3, Day),
+    );
+    assert_eq!((first, second), (1, 2));
+    assert_eq!(schedule.events_by_id(2).next().unwrap().name, "Eclipse");
+}
+
+#[test]
+fn events_between() {
+    let mut schedule = Scheduler::new();
+    schedule.schedule(
+        "Festival",
+        Category::Story,
+        Time::from(1, Day),
+        Time::from(2, Day),
+    );
+    schedule.schedule(
+        "Bless",
+        Category::Effect,
+        Time::from(1, Minute),
+        Time::from(2, Minute),
+    );
+    let names = |from: Time, until: Time| {
+        schedule
+            .events_between(&from, &until)
+            .map(|e| e.name.clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(names(Time::new(), Time::from(1, Day)), vec!["Bless"]);
+    assert_eq!(
+        names(Time::from(2, Day), Time::from(3, Day)),
+        vec!["Festival"]
+    );
+    assert!(names(Time::from(3, Day), Time::from(4, Day)).is_empty());
+}
+
+#[test]
+fn approximate_durations() {
+    assert_eq!(approximate(1), "1 round");
+    assert_eq!(approximate(24), "4 rounds");
+    assert_eq!(approximate(90), "1 minute");
+    assert_eq!(approximate(Time::from(3, Day).value + 5), "3 days");
+    assert_eq!(approximate(Time::from(2, Week).value), "2 weeks");
+}
+
+#[test]
+fn event_countdown() {
+    let bless = Event {
+        start: Time::new(),
+        end: Time::from(1, Minute),
+        id: 1,
+        name: "Bless".to_string(),
+        category: Category::Effect,
+    };
+    assert_eq!(
+        bless.countdown(&Time::from(36, Second)),
+        "Bless ends in 4 rounds"
+    );
+    assert_eq!(bless.countdown(&Time::from(1, Minute)), "Bless ends now");
+    assert_eq!(bless.countdown(&Time::from(1, Hour)), "Bless ended");
+
+    let festival = Event {
+        start: Time::from(3, Day),
+        end: Time::from(4, Day),
+        id: 2,
+        name: "Festival".to_string(),
+        category: Category::Story,
+    };
+    assert_eq!(festival.countdown(&Time::new()), "Festival in 3 days");
+}
+
+#[test]
+fn agenda_extend_and_cancel() {
+    let mut schedule = Scheduler::new();
+    let festival = schedule.schedule(
+        "Festival",
+        Category::Story,
+        Time::from(3, Day),
+        Time::from(4, Day),
+    );
+    let bless = schedule.schedule(
+        "Bless",
+        Category::Effect,
+        Time::new(),
+        Time::from(1, Minute),
+    );
+    let names = |schedule: &Scheduler| {
+        schedule
+            .agenda()
+            .iter()
+            .map(|e| e.name.clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(names(&schedule), vec!["Bless", "Festival"]);
+
+    schedule.advance(&Time::from(2, Minute));
+    assert_eq!(names(&schedule), vec!["Festival"]);
+
+    assert!(schedule.extend(bless, &Time::from(10, Minute)));
+    assert_eq!(names(&schedule), vec!["Bless", "Festival"]);
+    assert!(!schedule.extend(99, &Time::from(10, Minute)));
+
+    assert_eq!(schedule.cancel(festival).len(), 1);
+    assert_eq!(names(&schedule), vec!["Bless"]);
+    assert!(schedule.cancel(festival).is_empty());
+}
+
+#[test]
+fn extend_timer() {
+    let mut schedule = Scheduler::new();
+    let done = Time::from(1, Hour);
+    let ritual = schedule.schedule("Ritual", Category::Timer, done.clone(), done);
+    schedule.extend(ritual, &Time::from(30, Minute));
+    let timer = schedule.events_by_id(ritual).next().unwrap();
+    assert_eq!(timer.start, Time::from(90, Minute));
+    assert_eq!(timer.end, Time::from(90, Minute));
+    assert_eq!(
+        timer.countdown(&Time::from(1, Hour)),
+        "Ritual completes in 30 minutes"
+    );
+}
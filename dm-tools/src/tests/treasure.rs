@@ -0,0 +1,82 @@
+use crate::coin::Coin::*;
+use crate::coin::*;
+use crate::treasure::*;
+
+fn hoard(text: &str) -> Purse {
+    let mut purse = Purse::new();
+    for (coin, count) in parse_coins(text).unwrap() {
+        purse.add_coins(count, coin).unwrap();
+    }
+    purse
+}
+
+fn party(names: &[&str]) -> Vec<Share> {
+    names.iter().map(|name| Share::new(name, 1)).collect()
+}
+
+#[test]
+fn split_evenly() {
+    let split = split(
+        &hoard("90 gp 30 sp"),
+        &party(&["A", "B", "C"]),
+        SplitMode::NoConversion,
+    );
+    for name in ["A", "B", "C"].iter() {
+        let coins = split.get(name).unwrap();
+        assert_eq!(coins.get(&Gold), Some(&30));
+        assert_eq!(coins.get(&Silver), Some(&10));
+    }
+    assert_eq!(format_coins(&split.remainder), "0 cp");
+}
+
+#[test]
+fn no_conversion_remainder() {
+    let split = split(
+        &hoard("10 gp 5 sp 2 cp"),
+        &party(&["A", "B", "C"]),
+        SplitMode::NoConversion,
+    );
+    assert_eq!(split.get("A").unwrap().get(&Gold), Some(&3));
+    assert_eq!(split.get("A").unwrap().get(&Silver), Some(&1));
+    assert_eq!(split.get("A").unwrap().get(&Copper), Some(&0));
+    assert_eq!(format_coins(&split.remainder), "1 gp 2 sp 2 cp");
+}
+
+#[test]
+fn fewest_coins() {
+    let split = split(
+        &hoard("10 gp 5 sp 2 cp"),
+        &party(&["A", "B", "C"]),
+        SplitMode::FewestCoins,
+    );
+    // 1052 cp split three ways is 350 each, with 2 cp left over.
+    assert_eq!(format_coins(split.get("A").unwrap()), "3 gp 1 ep");
+    assert_eq!(format_coins(&split.remainder), "2 cp");
+}
+
+#[test]
+fn weighted_shares_and_fund() {
+    let shares = vec![Share::new("A", 2), Share::new("B", 1), Share::fund(1)];
+    let split = split(&hoard("100 gp"), &shares, SplitMode::NoConversion);
+    assert_eq!(split.get("A").unwrap().get(&Gold), Some(&50));
+    assert_eq!(split.get("B").unwrap().get(&Gold), Some(&25));
+    assert_eq!(split.get(PARTY_FUND).unwrap().get(&Gold), Some(&25));
+}
+
+#[test]
+fn no_shares() {
+    let split = split(&hoard("7 gp"), &[], SplitMode::FewestCoins);
+    assert!(split.shares.is_empty());
+    assert_eq!(format_coins(&split.remainder), "7 gp");
+}
+
+#[test]
+fn share_from_str() {
+    assert_eq!("Tordek".parse::<Share>().unwrap(), Share::new("Tordek", 1));
+    assert_eq!(
+        " Mialee : 2".parse::<Share>().unwrap(),
+        Share::new("Mialee", 2)
+    );
+    assert!("Lidda:x".parse::<Share>().is_err());
+    assert!(":2".parse::<Share>().is_err());
+}
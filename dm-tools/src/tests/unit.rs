@@ -2,6 +2,7 @@ use crate::tests::unit::FooUnit::*;
 use crate::unit::*;
 use dm_tools_derive::Unit;
 use num_derive::{FromPrimitive, ToPrimitive};
+use strum::AsStaticRef;
 use strum_macros::AsStaticStr;
 
 #[derive(
@@ -80,6 +81,51 @@ fn distribute_from_one_unit() {
     assert_eq!(res.get(&Two), Some(61 as i64).as_ref());
 }
 
+#[test]
+fn derive_default_names() {
+    assert_eq!(Two.name(), "Two");
+    assert_eq!(Two.short(), "Two");
+    assert_eq!(Two.plural(), "Twos");
+}
+
+#[test]
+fn derive_unit_attributes() {
+    #[derive(
+        FromPrimitive,
+        ToPrimitive,
+        Hash,
+        Debug,
+        Copy,
+        Clone,
+        PartialEq,
+        Eq,
+        Unit,
+        Ord,
+        PartialOrd,
+        AsStaticStr,
+    )]
+    enum Length {
+        #[unit(value = 1, short = "in", plural = "Inches")]
+        Inch,
+        #[unit(value = 12, short = "ft", plural = "Feet")]
+        Foot,
+        #[unit(value = 36, name = "Yard", short = "yd")]
+        Yd,
+    }
+
+    assert_eq!(Length::Inch.value(), 1);
+    assert_eq!(Length::Foot.value(), 12);
+    assert_eq!(Length::Foot.name(), "Foot");
+    assert_eq!(Length::Foot.short(), "ft");
+    assert_eq!(Length::Foot.plural(), "Feet");
+    assert_eq!(Length::Yd.name(), "Yard");
+    assert_eq!(Length::Yd.plural(), "Yards");
+
+    let res = Length::distribute(40);
+    assert_eq!(res.get(&Length::Yd), Some(&1));
+    assert_eq!(res.get(&Length::Inch), Some(&4));
+}
+
 #[test]
 fn convert_value() {
     assert_eq!(One.value() * 100 / Two.value(), 50);
@@ -191,3 +237,343 @@ fn counter_distribute_overflow_with_existing() {
     assert_eq!(counter.get_count(&FooUnit::Two).unwrap(), 1);
     assert_eq!(counter.get_count(&FooUnit::Three).unwrap(), 2);
 }
+
+#[test]
+fn derive_divisible() {
+    use crate::coin::Coin;
+    use crate::time::UnitTime;
+
+    assert!(!FooUnit::divisible());
+    assert!(Coin::divisible());
+    assert!(UnitTime::divisible());
+}
+
+/// Written out by hand, relying on the trait's defaults.
+#[derive(
+    FromPrimitive,
+    ToPrimitive,
+    Debug,
+    Hash,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Ord,
+    PartialOrd,
+    AsStaticStr,
+)]
+enum Pace {
+    Step = 1,
+    Stride = 2,
+    League = 5,
+}
+
+impl Unit for Pace {
+    fn variants() -> &'static [Self] {
+        &[Pace::Step, Pace::Stride, Pace::League]
+    }
+
+    fn name(&self) -> &'static str {
+        self.as_static()
+    }
+
+    fn short(&self) -> &'static str {
+        self.as_static()
+    }
+
+    fn plural(&self) -> &'static str {
+        self.as_static()
+    }
+}
+
+#[test]
+fn hand_written_divisible() {
+    assert!(!Pace::divisible());
+    let res = Pace::distribute(6);
+    assert_eq!(res.get(&Pace::League), Some(&1));
+    assert_eq!(res.get(&Pace::Step), Some(&1));
+}
+
+#[test]
+fn counter_distribute_not_divisible() {
+    let mut counter = UnitCounter::<FooUnit>::new();
+    counter.set_units(4, &FooUnit::Two).unwrap();
+    counter.redistribute().unwrap();
+    // 3 Twos carry over as 2 Threes, the last one can't.
+    assert_eq!(counter.get_count(&FooUnit::Two).unwrap(), 1);
+    assert_eq!(counter.get_count(&FooUnit::Three).unwrap(), 2);
+}
+
+#[test]
+fn counter_distribute_not_divisible_steal() {
+    let mut counter = UnitCounter::<FooUnit>::new();
+    counter.set_units(-1, &FooUnit::Two).unwrap();
+    counter.set_units(2, &FooUnit::Three).unwrap();
+    counter.redistribute().unwrap();
+    assert_eq!(counter.get_count(&FooUnit::Two).unwrap(), 2);
+    assert_eq!(counter.get_count(&FooUnit::Three).unwrap(), 0);
+}
+
+#[test]
+fn counter_distribute_not_divisible_break() {
+    // A whole group would be 2 Threes, but breaking the one is enough.
+    let mut counter = UnitCounter::<FooUnit>::new();
+    counter.set_units(-1, &FooUnit::Two).unwrap();
+    counter.set_units(1, &FooUnit::Three).unwrap();
+    counter.redistribute().unwrap();
+    assert_eq!(counter.get_count(&FooUnit::One).unwrap(), 1);
+    assert_eq!(counter.get_count(&FooUnit::Two).unwrap(), 0);
+    assert_eq!(counter.get_count(&FooUnit::Three).unwrap(), 0);
+}
+
+#[test]
+fn counter_distribute_not_divisible_not_enough() {
+    let mut counter = UnitCounter::<FooUnit>::new();
+    counter.set_units(-2, &FooUnit::Two).unwrap();
+    counter.set_units(1, &FooUnit::Three).unwrap();
+    assert_eq!(
+        counter.redistribute(),
+        Err(CountError::NotEnough {
+            unit: "One",
+            requested: 4,
+            available: 3,
+        })
+    );
+    assert_eq!(counter.get_count(&FooUnit::Two).unwrap(), -2);
+    assert_eq!(counter.get_count(&FooUnit::Three).unwrap(), 1);
+}
+
+#[test]
+fn counter_errors() {
+    let mut counter = UnitCounter::<FooUnit>::new_with_units(&[One, Two]);
+    assert_eq!(
+        counter.get_count(&Three),
+        Err(CountError::UnitNotFound("Three".to_string()))
+    );
+    assert!(matches!(
+        counter.set_from_text("lots", &One),
+        Err(CountError::InvalidValue { ref text, .. }) if text == "lots"
+    ));
+
+    counter.set_units(3, &One).unwrap();
+    counter.set_units(-4, &Two).unwrap();
+    assert_eq!(
+        counter.redistribute(),
+        Err(CountError::NotEnough {
+            unit: "One",
+            requested: 8,
+            available: 3,
+        })
+    );
+}
+
+#[test]
+fn counter_distribute_underflow_untouched() {
+    let mut counter = UnitCounter::<FooUnit>::new();
+    counter.set_units(1, &FooUnit::One).unwrap();
+    counter.set_units(1, &FooUnit::Two).unwrap();
+    counter.sub_units(2, &FooUnit::Three).unwrap();
+    assert!(counter.redistribute().is_err());
+    assert_eq!(counter.get_count(&FooUnit::One).unwrap(), 1);
+    assert_eq!(counter.get_count(&FooUnit::Two).unwrap(), 1);
+    assert_eq!(counter.get_count(&FooUnit::Three).unwrap(), -2);
+}
+
+#[test]
+fn counter_try_sub() {
+    let mut counter = UnitCounter::<FooUnit>::new();
+    counter.set_units(2, &FooUnit::Three).unwrap();
+    counter.try_sub(1).unwrap();
+    // Borrowing breaks both Threes into Twos.
+    assert_eq!(counter.get_count(&FooUnit::One).unwrap(), 1);
+    assert_eq!(counter.get_count(&FooUnit::Two).unwrap(), 2);
+    assert_eq!(counter.get_count(&FooUnit::Three).unwrap(), 0);
+}
+
+#[test]
+fn counter_try_sub_not_divisible() {
+    let mut counter = UnitCounter::<FooUnit>::new();
+    counter.set_units(1, &FooUnit::Three).unwrap();
+    counter.try_sub(1).unwrap();
+    assert_eq!(counter.get_count(&FooUnit::One).unwrap(), 0);
+    assert_eq!(counter.get_count(&FooUnit::Two).unwrap(), 1);
+    assert_eq!(counter.get_count(&FooUnit::Three).unwrap(), 0);
+}
+
+#[test]
+fn counter_try_sub_rest_not_countable() {
+    let mut counter = UnitCounter::<FooUnit>::new_with_units(&[Two, Three]);
+    counter.set_units(1, &Three).unwrap();
+    match counter.try_sub(2) {
+        Err(CountError::InvalidValue { .. }) => {}
+        other => panic!("{:?}", other),
+    }
+    assert_eq!(counter.get_count(&Three).unwrap(), 1);
+}
+
+#[test]
+fn counter_try_sub_not_enough() {
+    let mut counter = UnitCounter::<FooUnit>::new();
+    counter.set_units(1, &FooUnit::One).unwrap();
+    counter.set_units(1, &FooUnit::Three).unwrap();
+    let err = counter.try_sub(7).unwrap_err();
+    assert_eq!(err.shortfall(), Some(3));
+    assert_eq!(counter.get_count(&FooUnit::One).unwrap(), 1);
+    assert_eq!(counter.get_count(&FooUnit::Two).unwrap(), 0);
+    assert_eq!(counter.get_count(&FooUnit::Three).unwrap(), 1);
+}
+
+#[test]
+fn counter_try_sub_reports_request() {
+    use crate::coin::Coin;
+
+    let mut counter = UnitCounter::<Coin>::new();
+    counter.set_units(1, &Coin::Gold).unwrap();
+    assert_eq!(
+        counter.try_sub(500),
+        Err(CountError::NotEnough {
+            unit: "cp",
+            requested: 500,
+            available: 100,
+        })
+    );
+    assert_eq!(counter.get_count(&Coin::Gold).unwrap(), 1);
+}
+
+#[test]
+fn counter_try_sub_normalizes() {
+    use crate::coin::Coin;
+
+    let mut counter = UnitCounter::<Coin>::new();
+    counter.set_units(150, &Coin::Copper).unwrap();
+    counter.set_units(1, &Coin::Gold).unwrap();
+    counter.try_sub(10).unwrap();
+    assert_eq!(counter.to_string(), "2 gp 4 sp");
+}
+
+#[test]
+fn counter_atomic_rolls_back() {
+    let mut counter = UnitCounter::<FooUnit>::new_with_units(&[One, Two]);
+    let result = counter.atomic(|c| {
+        c.add_units(5, &One)?;
+        c.add_units(1, &Three)
+    });
+    assert!(result.is_err());
+    assert_eq!(counter.get_count(&One).unwrap(), 0);
+}
+
+#[test]
+fn distribute_large_value() {
+    use crate::time::UnitTime;
+
+    let year = UnitTime::Year.value() as usize;
+    let res = UnitTime::distribute(year * 1000 + 61);
+    assert_eq!(res.get(&UnitTime::Year), Some(&1000));
+    assert_eq!(res.get(&UnitTime::Minute), Some(&1));
+    assert_eq!(res.get(&UnitTime::Second), Some(&1));
+}
+
+#[test]
+fn distribute_bounded_uses_minimum_steps() {
+    #[derive(
+        FromPrimitive,
+        ToPrimitive,
+        Hash,
+        Debug,
+        Copy,
+        Clone,
+        PartialEq,
+        Eq,
+        Unit,
+        Ord,
+        PartialOrd,
+        AsStaticStr,
+    )]
+    enum Coins {
+        One = 1,
+        Four = 4,
+        Nine = 9,
+    }
+
+    // Fewest coins for every value, worked out the slow way.
+    let mut fewest = vec![0usize; 200];
+    for i in 1..fewest.len() {
+        fewest[i] = Coins::variants()
+            .iter()
+            .map(|c| c.value() as usize)
+            .filter(|v| *v <= i)
+            .map(|v| fewest[i - v] + 1)
+            .min()
+            .unwrap();
+    }
+    for (value, steps) in fewest.iter().enumerate() {
+        let res = Coins::distribute(value);
+        let total: i64 = res.iter().map(|(c, n)| c.value_from_count(*n)).sum();
+        assert_eq!(total, value as i64);
+        assert_eq!(res.values().sum::<i64>(), *steps as i64, "{}", value);
+    }
+}
+
+#[test]
+fn counter_add_large_value() {
+    use crate::time::UnitTime;
+
+    let mut counter = UnitCounter::<UnitTime>::new();
+    counter.add(UnitTime::Year.value() * 50).unwrap();
+    assert_eq!(counter.get_count(&UnitTime::Year).unwrap(), 50);
+}
+
+#[test]
+fn counter_total() {
+    let mut counter = UnitCounter::<FooUnit>::new();
+    counter.set_units(2, &One).unwrap();
+    counter.set_units(3, &Three).unwrap();
+    assert_eq!(counter.total(), 11);
+    assert_eq!(counter.total_in(&Three), (3, 2));
+    assert_eq!(counter.total_in(&Two), (5, 1));
+}
+
+#[test]
+fn counter_convert_to() {
+    use crate::coin::Coin;
+
+    let mut purse = UnitCounter::<Coin>::new();
+    purse.set_units(3, &Coin::Gold).unwrap();
+    purse.set_units(45, &Coin::Silver).unwrap();
+    purse.set_units(7, &Coin::Copper).unwrap();
+
+    let (gold, rem) = purse.convert_to(&[Coin::Gold]);
+    assert_eq!(gold.get_count(&Coin::Gold).unwrap(), 7);
+    assert_eq!(rem, 57);
+    assert_eq!(gold.total() + rem, purse.total());
+
+    let (silver, rem) = purse.convert_to(&[Coin::Silver, Coin::Copper]);
+    assert_eq!(silver.to_string(), "75 sp 7 cp");
+    assert_eq!(rem, 0);
+}
+
+#[test]
+fn counter_compare() {
+    use crate::coin::Coin;
+
+    let mut gold = UnitCounter::<Coin>::new();
+    gold.set_units(1, &Coin::Gold).unwrap();
+    let mut silver = UnitCounter::<Coin>::new();
+    silver.set_units(10, &Coin::Silver).unwrap();
+    assert_eq!(gold, silver);
+
+    silver.add_units(1, &Coin::Copper).unwrap();
+    assert!(silver > gold);
+    assert!(gold < silver);
+}
+
+#[test]
+fn counter_display() {
+    use crate::coin::Coin;
+
+    let mut counter = UnitCounter::<Coin>::new();
+    assert_eq!(counter.to_string(), "0 cp");
+    counter.set_units(4, &Coin::Silver).unwrap();
+    counter.set_units(3, &Coin::Gold).unwrap();
+    assert_eq!(counter.to_string(), "3 gp 4 sp");
+}
@@ -0,0 +1,340 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::character::{Ability, Character};
+use crate::resource::*;
+use crate::time::UnitTime::*;
+use crate::time::*;
+use crate::world::{TimelineError, World, MAIN_TIMELINE};
+
+fn rng() -> StdRng {
+    StdRng::seed_from_u64(7)
+}
+
+fn fighter() -> Character {
+    let mut character = Character::new("Tordek", "Fighter", 5);
+    character.hit_die = 10;
+    character.abilities.set(Ability::Constitution, 14);
+    character.max_hp = 44;
+    character.hp = 10;
+    character
+        .resources
+        .push(Resource::new("Second Wind", 1, Recovery::ShortRest));
+    character
+        .resources
+        .push(Resource::new("Indomitable", 1, Recovery::LongRest));
+    character
+}
+
+fn party() -> World {
+    let mut world = World::new();
+    world.party.push(fighter());
+    world.party.push(fighter());
+    for character in world.party.iter_mut() {
+        character.resources.spend("Second Wind", 1);
+        character.resources.spend("Indomitable", 1);
+    }
+    world
+}
+
+#[test]
+fn spend_hit_die() {
+    let mut character = fighter();
+    let roll = character.spend_hit_die(&mut rng()).unwrap();
+    assert!((3..=12).contains(&roll));
+    assert_eq!(character.hp, 10 + roll);
+    assert_eq!(character.hit_dice, 4);
+
+    character.hit_dice = 0;
+    assert_eq!(character.spend_hit_die(&mut rng()), None);
+    assert_eq!(character.hp, 10 + roll);
+}
+
+#[test]
+fn hit_dice_never_overheal() {
+    let mut character = fighter();
+    character.hp = 43;
+    character.spend_hit_die(&mut rng());
+    assert_eq!(character.hp, 44);
+}
+
+#[test]
+fn short_rest() {
+    let mut world = party();
+    let report = world.rest(Rest::Short, &[2], &mut rng());
+    assert_eq!(world.time(), &Time::from(1, Hour));
+    assert_eq!(report.interrupted, None);
+
+    let (name, rolls) = &report.hit_dice[0];
+    assert_eq!(name, "Tordek");
+    assert_eq!(rolls.len(), 2);
+    let tordek = &world.party[0];
+    assert_eq!(tordek.hp, 10 + rolls.iter().sum::<i64>());
+    assert_eq!(tordek.hit_dice, 3);
+    assert_eq!(tordek.resources.get("Second Wind").unwrap().current, 1);
+    assert_eq!(tordek.resources.get("Indomitable").unwrap().current, 0);
+
+    // Missing entries spend nothing.
+    assert!(report.hit_dice[1].1.is_empty());
+    assert_eq!(world.party[1].hp, 10);
+}
+
+#[test]
+fn short_rest_runs_out_of_hit_dice() {
+    let mut world = party();
+    world.party[0].hit_dice = 1;
+    let report = world.rest(Rest::Short, &[3], &mut rng());
+    assert_eq!(report.hit_dice[0].1.len(), 1);
+    assert_eq!(world.party[0].hit_dice, 0);
+}
+
+#[test]
+fn long_rest() {
+    let mut world = party();
+    world.party[0].hit_dice = 0;
+    world.party[0].exhaustion = 2;
+    world.rest(Rest::Long, &[5], &mut rng());
+
+    let tordek = &world.party[0];
+    assert_eq!(world.time(), &Time::from(8, Hour));
+    assert_eq!(tordek.hp, 44);
+    assert_eq!(tordek.hit_dice, 2);
+    assert_eq!(tordek.exhaustion, 1);
+    assert_eq!(tordek.resources.get("Indomitable").unwrap().current, 1);
+
+    // Hit dice regained never exceed the character's level.
+    assert_eq!(world.party[1].hit_dice, 5);
+}
+
+#[test]
+fn exhaustion_is_capped() {
+    let mut character = fighter();
+    character.add_exhaustion(10);
+    assert_eq!(character.exhaustion, 6);
+    character.add_exhaustion(-10);
+    assert_eq!(character.exhaustion, 0);
+}
+
+#[test]
+fn interrupted_rest() {
+    let mut world = party();
+    let ambush = Event {
+        start: Time::from(3, Hour),
+        end: Time::from(4, Hour),
+        id: 1,
+        name: "Ambush".to_string(),
+        category: Category::Other,
+    };
+    world.scheduler.push(ambush.clone());
+    let report = world.rest(Rest::Long, &[], &mut rng());
+
+    assert_eq!(report.interrupted, Some(ambush));
+    assert_eq!(world.time(), &Time::from(3, Hour));
+    assert!(report.hit_dice.is_empty());
+    assert_eq!(world.party[0].hp, 10);
+    assert_eq!(
+        world.party[0].resources.get("Indomitable").unwrap().current,
+        0
+    );
+}
+
+#[test]
+fn rest_reports_events() {
+    let mut world = party();
+    world.scheduler.push(Event {
+        start: Time::from(30, Minute),
+        end: Time::from(2, Hour),
+        id: 1,
+        name: "Event 1".to_string(),
+        category: Category::Other,
+    });
+    world.scheduler.push(Event {
+        start: Time::from(2, Hour),
+        end: Time::from(3, Hour),
+        id: 2,
+        name: "Event 2".to_string(),
+        category: Category::Other,
+    });
+    let report = world.rest(Rest::Short, &[], &mut rng());
+    assert_eq!(report.interrupted.map(|e| e.id), Some(1));
+    assert_eq!(world.time(), &Time::from(30, Minute));
+}
+
+#[test]
+fn event_as_rest_ends_does_not_interrupt() {
+    let mut world = party();
+    world.scheduler.push(Event {
+        start: Time::from(1, Hour),
+        end: Time::from(2, Hour),
+        id: 1,
+        name: "Visitor".to_string(),
+        category: Category::Other,
+    });
+    let report = world.rest(Rest::Short, &[], &mut rng());
+    assert_eq!(report.interrupted, None);
+    assert_eq!(world.time(), &Time::from(1, Hour));
+    assert_eq!(
+        world.party[0].resources.get("Second Wind").unwrap().current,
+        1
+    );
+}
+
+#[test]
+fn rest_reports_expired_events() {
+    let mut world = party();
+    world.scheduler.push(Event {
+        start: Time::new(),
+        end: Time::from(30, Minute),
+        id: 1,
+        name: "Event 1".to_string(),
+        category: Category::Other,
+    });
+    world.scheduler.push(Event {
+        start: Time::new(),
+        end: Time::from(2, Hour),
+        id: 2,
+        name: "Event 2".to_string(),
+        category: Category::Other,
+    });
+    let report = world.rest(Rest::Short, &[], &mut rng());
+    assert_eq!(
+        report.expired.iter().map(|e| e.id).collect::<Vec<_>>(),
+        vec![1]
+    );
+}
+
+#[test]
+fn timers_fire_when_time_gets_there() {
+    let mut world = World::new();
+    let id = world.start_timer("Ritual", &Time::from(3, Hour));
+    let timer = world.scheduler.events_by_id(id).next().unwrap().clone();
+    assert_eq!(timer.category, Category::Timer);
+    assert_eq!(timer.countdown(world.time()), "Ritual completes in 3 hours");
+
+    assert!(world.advance(&Time::from(2, Hour)).is_empty());
+    let fired = world.advance(&Time::from(2, Hour));
+    assert_eq!(fired, vec![timer.clone()]);
+    assert_eq!(timer.countdown(world.time()), "Ritual completed");
+}
+
+#[test]
+fn timelines_diverge_and_switch() {
+    let mut world = World::new();
+    world.diverge("Downtime").unwrap();
+    assert_eq!(
+        world.diverge("Downtime"),
+        Err(TimelineError::Exists("Downtime".to_string()))
+    );
+    assert!(world.diverge(MAIN_TIMELINE).is_err());
+
+    world.switch_to("Downtime").unwrap();
+    world.advance(&Time::from(5, Day));
+    assert_eq!(world.timeline, "Downtime");
+    assert_eq!(world.timelines[0].name, MAIN_TIMELINE);
+    assert_eq!(world.timelines[0].scheduler.time, Time::new());
+
+    world.switch_to(MAIN_TIMELINE).unwrap();
+    assert_eq!(world.time(), &Time::new());
+    assert_eq!(world.timelines[0].scheduler.time, Time::from(5, Day));
+    assert_eq!(
+        world.switch_to("Travel").unwrap_err().to_string(),
+        "no other timeline is named \"Travel\""
+    );
+}
+
+#[test]
+fn sync_catches_up() {
+    let mut world = World::new();
+    world.diverge("Scouts").unwrap();
+    world.switch_to("Scouts").unwrap();
+    world.advance(&Time::from(2, Day));
+    world.switch_to(MAIN_TIMELINE).unwrap();
+    world.start_timer("Ritual", &Time::from(1, Day));
+
+    let started = world.sync("Scouts").unwrap();
+    assert_eq!(started.len(), 1);
+    assert_eq!(world.time(), &Time::from(2, Day));
+    assert!(world.timelines.is_empty());
+
+    // Syncing a timeline that fell behind leaves time alone.
+    world.diverge("Camp").unwrap();
+    world.advance(&Time::from(1, Hour));
+    assert!(world.sync("Camp").unwrap().is_empty());
+    assert_eq!(world.time(), &Time::from(49, Hour));
+}
+
+#[test]
+fn timer_stays_on_its_timeline() {
+    let mut world = World::new();
+    world.diverge("Scouts").unwrap();
+    world.switch_to("Scouts").unwrap();
+    world.start_timer("Lookout", &Time::from(1, Hour));
+
+    world.switch_to(MAIN_TIMELINE).unwrap();
+    assert!(world.scheduler.agenda().is_empty());
+    assert!(world.advance(&Time::from(2, Hour)).is_empty());
+
+    world.switch_to("Scouts").unwrap();
+    let fired = world.advance(&Time::from(2, Hour));
+    assert_eq!(fired.len(), 1);
+    assert_eq!(fired[0].name, "Lookout");
+}
+
+#[test]
+fn sync_takes_on_events() {
+    let mut world = World::new();
+    let ritual = world.start_timer("Ritual", &Time::from(1, Day));
+    world.diverge("Scouts").unwrap();
+    world.start_timer("Feast", &Time::from(2, Day));
+    world.switch_to("Scouts").unwrap();
+    world.start_timer("Lookout", &Time::from(3, Day));
+    world.switch_to(MAIN_TIMELINE).unwrap();
+
+    world.sync("Scouts").unwrap();
+    let names: Vec<&str> = world
+        .scheduler
+        .agenda()
+        .iter()
+        .map(|e| e.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["Ritual", "Feast", "Lookout"]);
+    assert_eq!(world.scheduler.events_by_id(ritual).count(), 1);
+    // Both timelines gave their second timer the same id.
+    let ids: Vec<u64> = world.scheduler.events().iter().map(|e| e.id).collect();
+    assert_eq!(ids, vec![1, 2, 3]);
+}
+
+#[test]
+fn dawn_restores_once_across_timelines() {
+    let mut world = World::new();
+    let mut wizard = Character::new("Mialee", "Wizard", 5);
+    wizard
+        .resources
+        .push(Resource::new("Wand charges", 7, Recovery::Dawn));
+    world.party.push(wizard);
+    world.diverge("Scouts").unwrap();
+    let charges = |world: &World| {
+        world.party[0]
+            .resources
+            .get("Wand charges")
+            .unwrap()
+            .current
+    };
+
+    world.party[0].resources.spend("Wand charges", 3);
+    world.advance(&Time::from(1, Day));
+    assert_eq!(charges(&world), 7);
+
+    world.party[0].resources.spend("Wand charges", 3);
+    world.switch_to("Scouts").unwrap();
+    world.advance(&Time::from(1, Day));
+    assert_eq!(charges(&world), 4);
+    world.advance(&Time::from(1, Day));
+    assert_eq!(charges(&world), 7);
+
+    // Main already saw the first dawn, Scouts the second.
+    world.party[0].resources.spend("Wand charges", 3);
+    world.switch_to(MAIN_TIMELINE).unwrap();
+    world.sync("Scouts").unwrap();
+    assert_eq!(charges(&world), 4);
+}
@@ -1,8 +1,12 @@
 use dm_tools_derive::Unit;
 use num_derive::{FromPrimitive, ToPrimitive};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use strum::AsStaticRef;
 use strum_macros::AsStaticStr;
 
-use crate::unit::Unit;
+use crate::unit::{CountError, Unit, UnitCounter};
 
 #[derive(
     Debug,
@@ -18,18 +22,32 @@ use crate::unit::Unit;
     PartialOrd,
     AsStaticStr,
 )]
+#[unit(divisible)]
 pub enum UnitTime {
+    #[unit(short = "s")]
     Second = 1,
+    #[unit(short = "min")]
     Minute = 60,
+    #[unit(short = "h")]
     Hour = 3600,
+    #[unit(short = "d")]
     Day = 86400,
+    #[unit(short = "wk")]
     Week = 604800,
+    #[unit(short = "mo")]
     Month = 2419200,
+    #[unit(short = "yr")]
     Year = 29030400,
 }
 
+/// Length of a combat round in seconds.
+pub const ROUND: i64 = 6 * UnitTime::Second as i64;
+
+/// Time of day the sun rises, in seconds after midnight.
+pub const DAWN: i64 = 6 * 3600;
+
 /// A tool for managing time and its units.
-#[derive(Eq, PartialEq, Ord, PartialOrd, Debug, Clone)]
+#[derive(Eq, PartialEq, Ord, PartialOrd, Debug, Clone, Serialize, Deserialize)]
 pub struct Time {
     pub value: i64,
 }
@@ -43,6 +61,84 @@ impl Time {
             value: unit.value() * num,
         }
     }
+
+    /// Number of dawns after `self`, up to and including `until`.
+    pub fn dawns_until(&self, until: &Time) -> i64 {
+        let day = UnitTime::Day.value();
+        let dawns = |t: &Time| (t.value - DAWN).div_euclid(day);
+        (dawns(until) - dawns(self)).max(0)
+    }
+
+    /// `self` as a length of time, e.g. "1 h 30 min" once displayed.
+    pub fn units(&self) -> Result<UnitCounter<UnitTime>, CountError> {
+        let mut units = UnitCounter::new();
+        units.add(self.value)?;
+        Ok(units)
+    }
+}
+
+impl fmt::Display for Time {
+    /// Day count and time of day, e.g. "Day 3, 14:05:00".
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let day = UnitTime::Day.value();
+        let (days, seconds) = (self.value.div_euclid(day), self.value.rem_euclid(day));
+        write!(
+            f,
+            "Day {}, {:02}:{:02}:{:02}",
+            days + 1,
+            seconds / UnitTime::Hour.value(),
+            seconds % UnitTime::Hour.value() / UnitTime::Minute.value(),
+            seconds % UnitTime::Minute.value()
+        )
+    }
+}
+
+/// Rough length of `seconds`, in rounds under a minute and otherwise in
+/// the largest whole unit, e.g. "4 rounds" or "3 days".
+pub fn approximate(seconds: i64) -> String {
+    if seconds < UnitTime::Minute.value() {
+        let rounds = (seconds + ROUND - 1).div_euclid(ROUND).max(1);
+        return format!("{} round{}", rounds, if rounds == 1 { "" } else { "s" });
+    }
+    let unit = UnitTime::variants()
+        .iter()
+        .rev()
+        .find(|unit| unit.value() <= seconds)
+        .expect("At least a minute");
+    let count = seconds / unit.value();
+    let name = if count == 1 {
+        unit.name()
+    } else {
+        unit.plural()
+    };
+    format!("{} {}", count, name.to_lowercase())
+}
+
+/// What kind of thing an event is, for filtering.
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq, AsStaticStr)]
+pub enum Category {
+    /// Spells, conditions and other effects on someone.
+    Effect,
+    Combat,
+    Travel,
+    Downtime,
+    Story,
+    /// Countdowns, done once they start.
+    Timer,
+    Other,
+}
+
+impl Category {
+    pub fn variants() -> &'static [Category] {
+        use Category::*;
+        &[Effect, Combat, Travel, Downtime, Story, Timer, Other]
+    }
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_static())
+    }
 }
 
 #[derive(PartialEq, Eq, Debug, Clone)]
@@ -50,8 +146,47 @@ pub struct Event {
     pub start: Time,
     pub end: Time,
     pub id: u64,
+    /// What happens, e.g. "Festival".
+    pub name: String,
+    pub category: Category,
 }
 
+impl Event {
+    /// Where the event stands at `now`, e.g. "Festival in 3 days" or
+    /// "Bless ends in 4 rounds".
+    pub fn countdown(&self, now: &Time) -> String {
+        if self.category == Category::Timer {
+            return if *now < self.start {
+                format!(
+                    "{} completes in {}",
+                    self.name,
+                    approximate(self.start.value - now.value)
+                )
+            } else {
+                format!("{} completed", self.name)
+            };
+        }
+        if *now < self.start {
+            format!(
+                "{} in {}",
+                self.name,
+                approximate(self.start.value - now.value)
+            )
+        } else if *now < self.end {
+            format!(
+                "{} ends in {}",
+                self.name,
+                approximate(self.end.value - now.value)
+            )
+        } else if *now == self.end {
+            format!("{} ends now", self.name)
+        } else {
+            format!("{} ended", self.name)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Scheduler {
     pub time: Time,
     events: Vec<Event>,
@@ -65,10 +200,85 @@ impl Scheduler {
         }
     }
 
+    /// Move time forward by `duration`, returning the events that started
+    /// along the way.
+    pub fn advance(&mut self, duration: &Time) -> Vec<Event> {
+        let from = self.time.clone();
+        self.time.value += duration.value;
+        self.events
+            .iter()
+            .filter(|e| e.start > from && e.start <= self.time)
+            .cloned()
+            .collect()
+    }
+
     pub fn push(&mut self, event: Event) {
         self.events.push(event)
     }
 
+    /// Add an event called `name` with an id no other event has, returning
+    /// the id.
+    pub fn schedule(&mut self, name: &str, category: Category, start: Time, end: Time) -> u64 {
+        let id = self.events.iter().map(|e| e.id + 1).max().unwrap_or(1);
+        self.push(Event {
+            start,
+            end,
+            id,
+            name: name.to_string(),
+            category,
+        });
+        id
+    }
+
+    /// Push back the end of the events with `event_id` by `duration`,
+    /// returning whether there were any. Timers complete at their start, so
+    /// that moves too.
+    pub fn extend(&mut self, event_id: u64, duration: &Time) -> bool {
+        let mut found = false;
+        for event in self.events.iter_mut().filter(|e| e.id == event_id) {
+            if event.category == Category::Timer {
+                event.start.value += duration.value;
+            }
+            event.end.value += duration.value;
+            found = true;
+        }
+        found
+    }
+
+    /// Add the events of `other` that aren't here already, e.g. when
+    /// timelines come back together. Ids taken here are swapped for new ones.
+    pub fn merge(&mut self, other: &Scheduler) {
+        let new: Vec<Event> = other
+            .events
+            .iter()
+            .filter(|e| !self.events.contains(e))
+            .cloned()
+            .collect();
+        let mut next = self.events.iter().map(|e| e.id + 1).max().unwrap_or(1);
+        let mut ids = HashMap::new();
+        for event in new.into_iter() {
+            let id = *ids.entry(event.id).or_insert_with(|| {
+                next += 1;
+                next - 1
+            });
+            self.push(Event { id, ..event });
+        }
+    }
+
+    /// Remove the events with `event_id`, returning them.
+    pub fn cancel(&mut self, event_id: u64) -> Vec<Event> {
+        let (cancelled, kept) = self.events.drain(..).partition(|e| e.id == event_id);
+        self.events = kept;
+        cancelled
+    }
+
+    /// Events that haven't ended yet, soonest first.
+    pub fn agenda(&self) -> Vec<&Event> {
+        let mut events: Vec<&Event> = self.events.iter().filter(|e| e.end >= self.time).collect();
+        events.sort_by_key(|e| (e.start.clone(), e.end.clone()));
+        events
+    }
+
     pub fn events(&self) -> &Vec<Event> {
         &self.events
     }
@@ -77,6 +287,18 @@ impl Scheduler {
         self.events().iter().filter(move |e| self.is_active(e))
     }
 
+    /// Events active at some point from `from` up to, but not including,
+    /// `until`.
+    pub fn events_between<'a>(
+        &'a self,
+        from: &'a Time,
+        until: &'a Time,
+    ) -> impl Iterator<Item = &'a Event> {
+        self.events()
+            .iter()
+            .filter(move |e| e.start < *until && e.end >= *from)
+    }
+
     pub fn events_by_id(&self, event_id: u64) -> impl Iterator<Item = &Event> {
         self.events().iter().filter(move |e| e.id == event_id)
     }
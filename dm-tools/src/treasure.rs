@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use crate::coin::{Coin, Purse};
+use crate::unit::{CountError, Unit};
+
+pub const PARTY_FUND: &str = "Party fund";
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SplitMode {
+    /// Split each coin type on its own, never exchanging coins.
+    NoConversion,
+    /// Split the total value, paying each share in as few coins as possible.
+    FewestCoins,
+}
+
+/// Someone's claim on the hoard, weighted against the other shares.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Share {
+    pub name: String,
+    pub weight: u32,
+}
+
+impl Share {
+    pub fn new(name: &str, weight: u32) -> Self {
+        Self {
+            name: name.to_string(),
+            weight,
+        }
+    }
+
+    /// A share kept aside for the whole party.
+    pub fn fund(weight: u32) -> Self {
+        Self::new(PARTY_FUND, weight)
+    }
+}
+
+impl FromStr for Share {
+    type Err = CountError;
+
+    /// Parse a name with an optional weight, e.g. "Tordek" or "Tordek:2".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, weight) = match s.rfind(':') {
+            Some(i) => (
+                &s[..i],
+                s[i + 1..]
+                    .trim()
+                    .parse::<u32>()
+                    .map_err(|e| CountError::invalid(&s[i + 1..], e))?,
+            ),
+            None => (s, 1),
+        };
+        match name.trim() {
+            "" => Err(CountError::invalid(s, "shares need a name")),
+            name => Ok(Self::new(name, weight)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Split {
+    /// Coins given to each share, in the order the shares were given.
+    pub shares: Vec<(Share, HashMap<Coin, i64>)>,
+    /// Coins that couldn't be divided fairly.
+    pub remainder: HashMap<Coin, i64>,
+}
+
+impl Split {
+    pub fn get(&self, name: &str) -> Option<&HashMap<Coin, i64>> {
+        self.shares
+            .iter()
+            .find(|(share, _)| share.name == name)
+            .map(|(_, coins)| coins)
+    }
+}
+
+/// Divide `hoard` between `shares` in proportion to their weights.
+///
+/// Each share gets the largest amount that can be given to everyone fairly,
+/// anything left over is returned as the remainder.
+pub fn split(hoard: &Purse, shares: &[Share], mode: SplitMode) -> Split {
+    let total_weight: i64 = shares.iter().map(|s| i64::from(s.weight)).sum();
+    if total_weight == 0 {
+        return Split {
+            shares: shares.iter().map(|s| (s.clone(), HashMap::new())).collect(),
+            remainder: hoard.counts(),
+        };
+    }
+
+    match mode {
+        SplitMode::NoConversion => {
+            let mut remainder = HashMap::new();
+            let mut result: Vec<_> = shares.iter().map(|s| (s.clone(), HashMap::new())).collect();
+            for coin in Coin::variants().iter() {
+                let count = hoard.get_count(*coin);
+                let mut left = count;
+                for (share, coins) in result.iter_mut() {
+                    let given = count * i64::from(share.weight) / total_weight;
+                    left -= given;
+                    coins.insert(*coin, given);
+                }
+                remainder.insert(*coin, left);
+            }
+            Split {
+                shares: result,
+                remainder,
+            }
+        }
+        SplitMode::FewestCoins => {
+            let value = hoard.total();
+            let mut left = value;
+            let result = shares
+                .iter()
+                .map(|share| {
+                    let given = value * i64::from(share.weight) / total_weight;
+                    left -= given;
+                    (share.clone(), Coin::distribute(given as usize))
+                })
+                .collect();
+            Split {
+                shares: result,
+                remainder: Coin::distribute(left as usize),
+            }
+        }
+    }
+}
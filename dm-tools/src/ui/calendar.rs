@@ -0,0 +1,164 @@
+use gtk::{BoxExt, ButtonExt, ContainerExt, GridExt, LabelExt, OrientableExt, WidgetExt};
+use relm::{connect, connect_stream, Relm, Widget};
+use relm_derive::{widget, Msg};
+use std::collections::BTreeSet;
+
+use crate::calendar::{Calendar, Date, DAYS_IN_MONTH, DAYS_IN_WEEK};
+use crate::time::Time;
+use crate::ui::text::Markup;
+
+#[derive(Msg)]
+pub enum Msg {
+    /// Show the month `Time` falls in, marking its day as today.
+    SetTime(Time),
+    /// Days to mark as having events.
+    Mark(BTreeSet<Date>),
+    PreviousMonth,
+    NextMonth,
+    Click(i64),
+    /// Emitted with the day clicked.
+    Selected(Date),
+}
+
+pub struct Model {
+    relm: Relm<CalendarView>,
+    calendar: Calendar,
+    /// First day of the month shown.
+    month: Date,
+    today: Date,
+    selected: Option<Date>,
+    marked: BTreeSet<Date>,
+    days: Vec<(gtk::Button, gtk::Label)>,
+}
+
+/// A month of the campaign calendar, with days that have events marked.
+#[widget]
+impl Widget for CalendarView {
+    fn model(relm: &Relm<Self>, calendar: Calendar) -> Model {
+        let today = Date::from_index(0);
+        Model {
+            relm: relm.clone(),
+            calendar,
+            month: today.first_of_month(),
+            today,
+            selected: None,
+            marked: BTreeSet::new(),
+            days: Vec::new(),
+        }
+    }
+
+    fn init_view(&mut self) {
+        for (weekday, name) in self.model.calendar.weekdays.iter().enumerate() {
+            let label = gtk::Label::new(None);
+            label.set_markup(&glib::markup_escape_text(name).markup_bold());
+            self.grid.attach(&label, weekday as i32, 0, 1, 1);
+        }
+        // Months are whole weeks, so every month is laid out the same.
+        let offset = self.model.month.weekday();
+        for day in 0..DAYS_IN_MONTH {
+            let cell = offset + day;
+            let button = gtk::Button::new();
+            let label = gtk::Label::new(None);
+            button.add(&label);
+            button.set_relief(gtk::ReliefStyle::None);
+            connect!(self.model.relm, button, connect_clicked(_), Msg::Click(day));
+            self.grid.attach(
+                &button,
+                (cell % DAYS_IN_WEEK) as i32,
+                (cell / DAYS_IN_WEEK + 1) as i32,
+                1,
+                1,
+            );
+            self.model.days.push((button, label));
+        }
+        self.grid.show_all();
+        self.update_days();
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Msg::SetTime(time) => {
+                self.model.today = Date::from_time(&time);
+                self.model.month = self.model.today.first_of_month();
+            }
+            Msg::Mark(marked) => self.model.marked = marked,
+            Msg::PreviousMonth => self.model.month = self.model.month.add_months(-1),
+            Msg::NextMonth => self.model.month = self.model.month.add_months(1),
+            Msg::Click(day) => {
+                let date = Date {
+                    day,
+                    ..self.model.month
+                };
+                self.model.selected = Some(date);
+                self.model.relm.stream().emit(Msg::Selected(date));
+            }
+            // For the parent.
+            Msg::Selected(_) => {}
+        }
+        self.update_days();
+    }
+
+    view! {
+        gtk::Box {
+            orientation: gtk::Orientation::Vertical,
+            spacing: 5,
+
+            gtk::Box {
+                gtk::Button {
+                    label: "<",
+                    clicked => Msg::PreviousMonth,
+                },
+                #[name="title"]
+                gtk::Label {
+                    hexpand: true,
+                    use_markup: true,
+                },
+                gtk::Button {
+                    label: ">",
+                    clicked => Msg::NextMonth,
+                },
+            },
+            #[name="grid"]
+            gtk::Grid {
+                row_homogeneous: true,
+                column_homogeneous: true,
+            },
+        }
+    }
+}
+
+impl CalendarView {
+    fn update_days(&mut self) {
+        let month = self.model.month;
+        let title = format!(
+            "{}, year {}",
+            glib::markup_escape_text(self.model.calendar.month_name(&month)),
+            month.year
+        );
+        self.title.set_markup(&title.markup_bold());
+
+        for (day, (button, label)) in self.model.days.iter().enumerate() {
+            let date = Date {
+                day: day as i64,
+                ..month
+            };
+            // A dot under days with events, today in bold.
+            let mark = if self.model.marked.contains(&date) {
+                "•"
+            } else {
+                " "
+            };
+            let text = format!("{}\n{}", day + 1, mark);
+            if date == self.model.today {
+                label.set_markup(&text.markup_bold());
+            } else {
+                label.set_text(&text);
+            }
+            button.set_relief(if Some(date) == self.model.selected {
+                gtk::ReliefStyle::Normal
+            } else {
+                gtk::ReliefStyle::None
+            });
+        }
+    }
+}
@@ -1,26 +1,36 @@
-use gtk::{BoxExt, LabelExt, WidgetExt};
+use gtk::{BoxExt, Inhibit, LabelExt, OrientableExt, RevealerExt, WidgetExt};
 use itertools::Itertools;
-use relm::{Component, Widget};
+use relm::{connect, connect_stream, Relm, Widget};
 use relm_derive::{widget, Msg};
 
-use crate::time::UnitTime;
-use crate::ui::count::{Counter, CounterMsg};
-use crate::ui::edit::EditView;
+use crate::time::{Time, UnitTime};
+use crate::ui::count::UnitCounterMsg::Changed as Edited;
+use crate::ui::count::{CounterOptions, UnitCounterMsg, UnitCounterView};
 use crate::ui::text::Markup;
 use crate::unit::{Unit, UnitCounter};
 
-type TimeCounter = Counter<UnitTime, ClockDisplay>;
+#[derive(Msg)]
+pub enum Msg {
+    ToggleReveal,
+    /// Show `Time` without emitting `Changed`, e.g. after time was advanced
+    /// elsewhere.
+    Set(Time),
+    Edit(UnitCounter<UnitTime>),
+    /// Emitted with the new time after the user edits it.
+    Changed(Time),
+}
 
-pub struct DisplayModel {
-    _counter: Option<Component<TimeCounter>>,
+pub struct Model {
+    relm: Relm<Clock>,
     count: UnitCounter<UnitTime>,
 }
 
+/// Date and time, edited per unit after clicking on them.
 #[widget]
-impl Widget for ClockDisplay {
-    fn model(_counter: Option<Component<TimeCounter>>) -> DisplayModel {
-        DisplayModel {
-            _counter,
+impl Widget for Clock {
+    fn model(relm: &Relm<Self>, _: ()) -> Model {
+        Model {
+            relm: relm.clone(),
             count: UnitCounter::new(),
         }
     }
@@ -29,45 +39,76 @@ impl Widget for ClockDisplay {
         self.update_datetime();
     }
 
-    fn update(&mut self, event: CounterMsg<UnitTime>) {
+    fn update(&mut self, event: Msg) {
         match event {
-            CounterMsg::Change(text, unit) => self
-                .model
-                .count
-                .set_from_text(&text, &unit)
-                .unwrap_or_default(),
-            CounterMsg::Increment(unit) => self.model.count.add_units(1, &unit).unwrap_or_default(),
-            CounterMsg::Decrement(unit) => self.model.count.sub_units(1, &unit).unwrap_or_default(),
-            CounterMsg::Add(count, unit) => {
-                self.model.count.add_units(count, &unit).unwrap_or_default()
+            Msg::ToggleReveal => self.edit.set_reveal_child(!self.edit.get_reveal_child()),
+            Msg::Set(time) => {
+                if time.value < 0 {
+                    return self
+                        .error
+                        .set_text(&format!("Can't show {}, before the clock starts", time));
+                }
+                let count = time.units().expect("Every unit is counted");
+                self.model.count = count.clone();
+                self.counter.stream().emit(UnitCounterMsg::Set(count));
+                self.error.set_text("");
+                self.update_datetime();
             }
+            Msg::Edit(count) => {
+                let time = Time {
+                    value: count.total(),
+                };
+                self.model.count = count;
+                self.update_datetime();
+                self.model.relm.stream().emit(Msg::Changed(time));
+            }
+            // For the parent.
+            Msg::Changed(_) => {}
         }
-        self.model.count.redistribute().unwrap_or_default();
-        self.update_datetime();
     }
 
     view! {
         gtk::Box {
-            halign: gtk::Align::Center,
-            hexpand: true,
-
-            #[name="label_date"]
-            gtk::Label {
-                halign: gtk::Align::Start,
-                use_markup: true,
-                margin_end: 80,
+            orientation: gtk::Orientation::Vertical,
+            spacing: 20,
+
+            gtk::EventBox {
+                button_press_event(_, _) => (Msg::ToggleReveal, Inhibit(false)),
+
+                gtk::Box {
+                    halign: gtk::Align::Center,
+                    hexpand: true,
+
+                    #[name="label_date"]
+                    gtk::Label {
+                        halign: gtk::Align::Start,
+                        use_markup: true,
+                        margin_end: 80,
+                    },
+
+                    #[name="label_time"]
+                    gtk::Label {
+                        use_markup: true,
+                        margin_start: 80,
+                    },
+                },
             },
 
-            #[name="label_time"]
-            gtk::Label {
-                use_markup: true,
-                margin_start: 80,
+            #[name="error"]
+            gtk::Label {},
+
+            #[name="edit"]
+            gtk::Revealer {
+                #[name="counter"]
+                UnitCounterView<UnitTime>(CounterOptions::new().carry()) {
+                    Edited(ref count) => Msg::Edit(count.clone()),
+                },
             },
         }
     }
 }
 
-impl ClockDisplay {
+impl Clock {
     fn update_datetime(&mut self) {
         self.label_date.set_markup(&self.date());
         self.label_time.set_markup(&self.time());
@@ -98,25 +139,3 @@ impl ClockDisplay {
         text.markup_bold().markup_fontsize(40)
     }
 }
-
-pub struct Model {}
-
-#[derive(Msg)]
-pub enum Msg {}
-
-#[widget]
-impl Widget for Clock {
-    fn model() -> Model {
-        Model {}
-    }
-
-    fn update(&mut self, event: Msg) {
-        match event {}
-    }
-
-    view! {
-        EditView<ClockDisplay, Counter<UnitTime, ClockDisplay>> {
-            spacing: 20,
-        }
-    }
-}
@@ -1,18 +1,16 @@
 use gdk::enums::key;
 use gdk::{EventMask, ScrollDirection};
-use gtk::{BoxExt, ButtonExt, EntryExt, Inhibit, OrientableExt, WidgetExt, WidgetExtManual};
+use gtk::{
+    BoxExt, ButtonExt, EntryExt, Inhibit, LabelExt, OrientableExt, WidgetExt, WidgetExtManual,
+};
 use relm::{connect, connect_stream, Component, ContainerWidget, EventStream, Relm, Widget};
 use relm_derive::{widget, Msg};
 
-use crate::ui::text::TextEntry;
-use crate::unit::Unit;
+use crate::ui::keys;
+use crate::ui::text::{Markup, TextEntry};
+use crate::unit::{CountError, Unit, UnitCounter};
 
-#[derive(Msg)]
-pub enum CounterDisplayMsg<T> {
-    Count(i64, T),
-}
-
-#[derive(Msg)]
+#[derive(Clone, Msg)]
 pub enum CounterMsg<T: Unit> {
     Change(String, T),
     Add(i64, T),
@@ -20,54 +18,171 @@ pub enum CounterMsg<T: Unit> {
     Decrement(T),
 }
 
-pub struct CounterModel<T, E>
-where
-    T: Unit,
-    E: Widget<Msg = CounterMsg<T>>,
-{
-    display: Option<Component<E>>,
+impl<T: Unit> CounterMsg<T> {
+    /// The unit being edited.
+    pub fn unit(&self) -> T {
+        match *self {
+            CounterMsg::Change(_, unit)
+            | CounterMsg::Add(_, unit)
+            | CounterMsg::Increment(unit)
+            | CounterMsg::Decrement(unit) => unit,
+        }
+    }
+}
+
+/// Settings for a `UnitCounterView`.
+#[derive(Clone)]
+pub struct CounterOptions<T: Unit> {
+    /// Units to edit, see `UnitCounter::new_with_units`.
+    pub units: Vec<T>,
+    /// Carry counts over into larger units after every change, as with time.
+    pub carry: bool,
+}
+
+impl<T: Unit> CounterOptions<T> {
+    pub fn new() -> Self {
+        Self::with_units(T::variants())
+    }
+
+    pub fn with_units(units: &[T]) -> Self {
+        Self {
+            units: units.to_vec(),
+            carry: false,
+        }
+    }
+
+    pub fn carry(mut self) -> Self {
+        self.carry = true;
+        self
+    }
+}
+
+#[derive(Msg)]
+pub enum UnitCounterMsg<T: Unit> {
+    Edit(CounterMsg<T>),
+    /// Replace the count without emitting `Changed`, e.g. to clear it.
+    Set(UnitCounter<T>),
+    /// Emitted with the whole count after every edit.
+    Changed(UnitCounter<T>),
+}
+
+pub struct UnitCounterModel<T: Unit> {
+    relm: Relm<UnitCounterView<T>>,
+    edits: EventStream<CounterMsg<T>>,
     counters: Vec<Component<CounterEdit<T>>>,
+    carry: bool,
+    units: Vec<T>,
+    count: UnitCounter<T>,
 }
 
+/// A counter with its own count, for embedding wherever units are edited.
+/// Edits that would leave a count negative are refused with an error shown
+/// under the counter.
 #[widget]
-impl<T, E> Widget for Counter<T, E>
+impl<T> Widget for UnitCounterView<T>
 where
     T: Unit,
-    E: Widget<Msg = CounterMsg<T>>,
 {
-    fn model(display: Option<Component<E>>) -> CounterModel<T, E> {
-        CounterModel {
-            display,
+    fn model(relm: &Relm<Self>, options: CounterOptions<T>) -> UnitCounterModel<T> {
+        // Forward edits from each unit's editor.
+        let edits = EventStream::new();
+        let stream = relm.stream().clone();
+        edits.observe(move |msg: &CounterMsg<T>| stream.emit(UnitCounterMsg::Edit(msg.clone())));
+        UnitCounterModel {
+            relm: relm.clone(),
+            edits,
             counters: vec![],
+            carry: options.carry,
+            count: UnitCounter::new_with_units(&options.units),
+            units: options.units,
         }
     }
 
-    fn subscriptions(&mut self, relm: &Relm<Self>) {
-        // XXX Requires variants to be sorted
-        for unit in T::variants().iter().rev() {
+    fn init_view(&mut self) {
+        let mut units = self.model.units.clone();
+        units.sort();
+        for unit in units.iter().rev() {
             let widget = self
-                .container
-                .add_widget::<CounterEdit<T>>((relm.stream().clone(), *unit));
+                .editors
+                .add_widget::<CounterEdit<T>>((self.model.edits.clone(), *unit));
             self.model.counters.push(widget);
         }
+        self.update_count();
     }
 
-    fn update(&mut self, event: CounterMsg<T>) {
-        // Pass messages to display
-        self.model
-            .display
-            .as_ref()
-            .map(|display| display.stream().emit(event));
+    fn update(&mut self, event: UnitCounterMsg<T>) {
+        match event {
+            UnitCounterMsg::Edit(msg) => {
+                let carry = self.model.carry;
+                let result = self.model.count.atomic(|count| {
+                    let unit = msg.unit();
+                    let before = count.get_count(&unit)?;
+                    match msg {
+                        CounterMsg::Change(text, unit) => count.set_from_text(&text, &unit),
+                        CounterMsg::Add(n, unit) => count.add_units(n, &unit),
+                        CounterMsg::Increment(unit) => count.add_units(1, &unit),
+                        CounterMsg::Decrement(unit) => count.sub_units(1, &unit),
+                    }?;
+                    if carry {
+                        return count.redistribute();
+                    }
+                    match count.get_count(&unit)? {
+                        after if after < 0 => Err(CountError::NotEnough {
+                            unit: unit.short(),
+                            requested: before - after,
+                            available: before,
+                        }),
+                        _ => Ok(()),
+                    }
+                });
+                match result {
+                    Ok(()) => {
+                        self.error.set_text("");
+                        let count = self.model.count.clone();
+                        self.model
+                            .relm
+                            .stream()
+                            .emit(UnitCounterMsg::Changed(count));
+                    }
+                    Err(e) => self.error.set_text(&e.to_string()),
+                }
+            }
+            UnitCounterMsg::Set(count) => {
+                self.model.count = count;
+                self.error.set_text("");
+            }
+            // For the parent.
+            UnitCounterMsg::Changed(_) => {}
+        }
+        self.update_count();
     }
 
     view! {
-        #[name="container"]
         gtk::Box {
-            spacing: 10,
+            orientation: gtk::Orientation::Vertical,
+            spacing: 5,
+
+            #[name="total"]
+            gtk::Label {
+                use_markup: true,
+            },
+            #[name="editors"]
+            gtk::Box {
+                spacing: 10,
+            },
+            #[name="error"]
+            gtk::Label {},
         }
     }
 }
 
+impl<T: Unit> UnitCounterView<T> {
+    fn update_count(&mut self) {
+        self.total
+            .set_markup(&self.model.count.to_string().markup_bold());
+    }
+}
+
 #[derive(Msg)]
 pub enum CounterEditMsg {
     Increment,
@@ -103,7 +218,7 @@ where
     fn init_view(&mut self) {
         self.entry
             .widget()
-            .set_placeholder_text(Some(self.model.unit.as_static()));
+            .set_placeholder_text(Some(self.model.unit.plural()));
         self.entry.widget().add_events(EventMask::SCROLL_MASK);
 
         self.inc.add_events(EventMask::BUTTON_RELEASE_MASK);
@@ -134,7 +249,7 @@ where
                     .emit(CounterMsg::Decrement(self.model.unit));
             }
             CounterEditMsg::Key(key, direction) => {
-                if let Some(value) = keymap(&key) {
+                if let Some(value) = keys::step(key) {
                     self.model
                         .stream
                         .emit(CounterMsg::Add(direction as i64 * value, self.model.unit));
@@ -188,19 +303,3 @@ where
         }
     }
 }
-
-fn keymap(key: &key::Key) -> Option<i64> {
-    match *key {
-        key::_1 | key::KP_1 | key::Return => Some(1),
-        key::_2 | key::KP_2 => Some(2),
-        key::_3 | key::KP_3 => Some(3),
-        key::_4 | key::KP_4 => Some(4),
-        key::_5 | key::KP_5 => Some(5),
-        key::_6 | key::KP_6 => Some(6),
-        key::_7 | key::KP_7 => Some(7),
-        key::_8 | key::KP_8 => Some(8),
-        key::_9 | key::KP_9 => Some(9),
-        key::_0 | key::KP_0 => Some(10),
-        _ => None,
-    }
-}
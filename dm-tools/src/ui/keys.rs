@@ -0,0 +1,147 @@
+use gdk::enums::key;
+use gtk::{BoxExt, FrameExt, GridExt, LabelExt, OrientableExt, WidgetExt};
+use relm::Widget;
+use relm_derive::{widget, Msg};
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use crate::keys::{self, Action, KeyBindings};
+use crate::ui::text::Markup;
+
+thread_local! {
+    static BINDINGS: (Rc<KeyBindings>, Option<String>) = load();
+}
+
+/// The user's key bindings, or the defaults and why they're used instead.
+fn load() -> (Rc<KeyBindings>, Option<String>) {
+    match KeyBindings::load() {
+        Ok(bindings) => (Rc::new(bindings), None),
+        Err(e) => {
+            let path = keys::config_path()
+                .map(|path| path.display().to_string())
+                .unwrap_or_default();
+            let error = format!(
+                "Could not load key bindings from {}, using the defaults: {}",
+                path, e
+            );
+            (Rc::new(KeyBindings::default()), Some(error))
+        }
+    }
+}
+
+/// Key bindings shared by every widget, loaded on first use.
+pub fn bindings() -> Rc<KeyBindings> {
+    BINDINGS.with(|(bindings, _)| Rc::clone(bindings))
+}
+
+/// Why the user's key bindings were ignored, if they were.
+pub fn load_error() -> Option<String> {
+    BINDINGS.with(|(_, error)| error.clone())
+}
+
+/// Amount releasing `key` on a counter button adds, see `KeyBindings::step`.
+pub fn step(key: key::Key) -> Option<i64> {
+    let name = gdk::keyval_name(key)?;
+    bindings().step(&name)
+}
+
+/// The shortcut pressed in `event`, if any.
+pub fn action(event: &gdk::EventKey) -> Option<Action> {
+    let modifiers = event.get_state() & gtk::accelerator_get_default_mod_mask();
+    let keyval = gdk::keyval_to_lower(event.get_keyval());
+    bindings()
+        .shortcuts
+        .iter()
+        .find(|shortcut| {
+            let (key, mods) = gtk::accelerator_parse(&shortcut.keys);
+            key != 0 && gdk::keyval_to_lower(key) == keyval && mods == modifiers
+        })
+        .map(|shortcut| shortcut.action)
+}
+
+/// Keys as shown to the user, e.g. "Ctrl+1" for "<Primary>1".
+pub fn label(keys: &str) -> String {
+    let (key, mods) = gtk::accelerator_parse(keys);
+    gtk::accelerator_get_label(key, mods)
+        .map(|label| label.to_string())
+        .unwrap_or_else(|| keys.to_string())
+}
+
+pub struct Model {}
+
+#[derive(Msg)]
+pub enum Msg {}
+
+/// Every shortcut and counter key, for showing over the window.
+#[widget]
+impl Widget for ShortcutsHelp {
+    fn model() -> Model {
+        Model {}
+    }
+
+    fn init_view(&mut self) {
+        let bindings = bindings();
+        let mut rows: Vec<(String, String)> = bindings
+            .shortcuts
+            .iter()
+            .map(|s| (label(&s.keys), s.action.to_string()))
+            .collect();
+
+        let mut steps: BTreeMap<i64, Vec<String>> = BTreeMap::new();
+        for (key, step) in bindings.counter_steps.iter() {
+            steps.entry(*step).or_default().push(label(key));
+        }
+        for (step, keys) in steps {
+            rows.push((keys.join(", "), format!("Add or remove {} on + or -", step)));
+        }
+
+        for (i, (keys, action)) in rows.iter().enumerate() {
+            let keys_label = gtk::Label::new(None);
+            keys_label.set_markup(&keys.markup_bold());
+            keys_label.set_halign(gtk::Align::End);
+            let action_label = gtk::Label::new(Some(action.as_str()));
+            action_label.set_halign(gtk::Align::Start);
+            self.grid.attach(&keys_label, 0, i as i32, 1, 1);
+            self.grid.attach(&action_label, 1, i as i32, 1, 1);
+        }
+        // The overlay hides the frame itself, keep its content ready to show.
+        self.content.show_all();
+        match load_error() {
+            Some(error) => self.error.set_text(&error),
+            None => self.error.hide(),
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {}
+    }
+
+    view! {
+        gtk::Frame {
+            label: Some("Shortcuts"),
+            halign: gtk::Align::Center,
+            valign: gtk::Align::Center,
+
+            #[name="content"]
+            gtk::Box {
+                orientation: gtk::Orientation::Vertical,
+                spacing: 10,
+                margin_top: 10,
+                margin_bottom: 10,
+                margin_start: 10,
+                margin_end: 10,
+
+                #[name="grid"]
+                gtk::Grid {
+                    row_spacing: 5,
+                    column_spacing: 20,
+                },
+                #[name="error"]
+                gtk::Label {
+                    line_wrap: true,
+                    max_width_chars: 60,
+                },
+            },
+        }
+    }
+}
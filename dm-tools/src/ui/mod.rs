@@ -1,5 +1,7 @@
+pub mod calendar;
 pub mod clock;
 pub mod count;
-pub mod edit;
+pub mod keys;
+pub mod purse;
 pub mod text;
 pub mod view;
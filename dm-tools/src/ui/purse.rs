@@ -0,0 +1,128 @@
+use gtk::{BoxExt, EntryExt, Inhibit, LabelExt, OrientableExt, RevealerExt, WidgetExt};
+use relm::{connect, connect_stream, Widget};
+use relm_derive::{widget, Msg};
+
+use crate::coin::{format_coins, parse_amount, Coin, Purse};
+use crate::ui::count::UnitCounterMsg::Changed as Edited;
+use crate::ui::count::{CounterOptions, UnitCounterMsg, UnitCounterView};
+use crate::ui::text::Markup;
+use crate::unit::{Unit, UnitCounter};
+
+#[derive(Msg)]
+pub enum Msg {
+    ToggleReveal,
+    Edit(UnitCounter<Coin>),
+    Pay(String),
+}
+
+pub struct Model {
+    purse: Purse,
+}
+
+/// A coin purse, edited per coin after clicking on it.
+#[widget]
+impl Widget for PurseView {
+    fn model() -> Model {
+        Model {
+            purse: Purse::new(),
+        }
+    }
+
+    fn init_view(&mut self) {
+        self.update_coins();
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Msg::ToggleReveal => self.edit.set_reveal_child(!self.edit.get_reveal_child()),
+            Msg::Edit(count) => {
+                let result = Coin::variants().iter().try_for_each(|coin| {
+                    let n = count.get_count(coin)?;
+                    self.model.purse.set_coins(n, *coin)
+                });
+                let status = match result {
+                    Ok(()) => String::new(),
+                    Err(e) => format!("Can't set the coins: {}", e),
+                };
+                self.status.set_text(&status);
+            }
+            Msg::Pay(text) => {
+                self.pay.set_text("");
+                let purse = &mut self.model.purse;
+                let status = match parse_amount(&text).and_then(|cost| purse.pay(cost)) {
+                    Ok(ref change) if change.is_empty() => format!("Paid {}", text.trim()),
+                    Ok(change) => format!(
+                        "Paid {}, received {} in change",
+                        text.trim(),
+                        format_coins(&change)
+                    ),
+                    Err(e) => format!("Can't pay: {}", e),
+                };
+                self.status.set_text(&status);
+                let coins = self.model.purse.coins().clone();
+                self.counter.stream().emit(UnitCounterMsg::Set(coins));
+            }
+        }
+        self.update_coins();
+    }
+
+    view! {
+        gtk::Box {
+            orientation: gtk::Orientation::Vertical,
+            spacing: 20,
+
+            gtk::EventBox {
+                button_press_event(_, _) => (Msg::ToggleReveal, Inhibit(false)),
+
+                gtk::Box {
+                    orientation: gtk::Orientation::Vertical,
+                    spacing: 10,
+                    halign: gtk::Align::Center,
+                    hexpand: true,
+
+                    #[name="label_coins"]
+                    gtk::Label {
+                        use_markup: true,
+                    },
+                    #[name="label_total"]
+                    gtk::Label {
+                        use_markup: true,
+                    },
+                },
+            },
+            gtk::Box {
+                spacing: 10,
+                halign: gtk::Align::Center,
+
+                #[name="pay"]
+                gtk::Entry {
+                    placeholder_text: Some("Pay, e.g. 7 gp 5 sp"),
+                    activate(entry) => Msg::Pay(
+                        entry.get_text().map(|t| t.to_string()).unwrap_or_default()
+                    ),
+                },
+                #[name="status"]
+                gtk::Label {},
+            },
+            #[name="edit"]
+            gtk::Revealer {
+                #[name="counter"]
+                UnitCounterView<Coin>(CounterOptions::new()) {
+                    Edited(ref count) => Msg::Edit(count.clone()),
+                },
+            },
+        }
+    }
+}
+
+impl PurseView {
+    fn update_coins(&mut self) {
+        let purse = &self.model.purse;
+        self.label_coins
+            .set_markup(&purse.coins().to_string().markup_bold().markup_fontsize(30));
+
+        let total = purse.total() as f64 / Coin::Gold.value() as f64;
+        self.label_total
+            .set_markup(&format!("Worth {:.2} gp", total));
+    }
+}
@@ -1,6 +1,9 @@
 use num::Integer;
 use num_traits::{FromPrimitive, ToPrimitive};
+use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
 use std::fmt::Debug;
 use std::hash::Hash;
 use strum::AsStaticRef;
@@ -21,41 +24,74 @@ pub trait Unit:
 {
     fn variants() -> &'static [Self];
 
+    /// Whether each unit's value divides the next, so counts always carry
+    /// over to the next unit without a remainder. Derived impls work this
+    /// out at compile time.
+    fn divisible() -> bool {
+        let mut units = Self::variants().to_vec();
+        units.sort();
+        each_divides(&units)
+    }
+
+    /// Defaults to the discriminant, or `#[unit(value = ...)]` when derived.
     fn value(&self) -> i64 {
         self.to_i64().expect("Error converting.")
     }
 
+    /// Display name, e.g. "Gold Piece".
+    fn name(&self) -> &'static str;
+
+    /// Abbreviation, e.g. "gp".
+    fn short(&self) -> &'static str;
+
+    /// Name for more than one, e.g. "Gold Pieces".
+    fn plural(&self) -> &'static str;
+
     fn value_from_count(&self, count: i64) -> i64 {
         self.value().saturating_mul(count)
     }
 
+    /// Distribute `value` into as few `units` as possible.
+    ///
+    /// Units that each divide the next are split greedily, largest first.
+    /// Other systems fall back to searching for the fewest units, bounded so
+    /// that large values stay cheap.
     fn distribute_from(units: &[Self], value: usize) -> HashMap<Self, i64> {
-        // Allocate space for all possible values that `value` could be distributed into.
-        let mut choices: Vec<Option<(usize, Self)>> = Vec::with_capacity(value + 1);
-        choices.resize(value + 1, None);
-
-        // Calculate number of steps required for each `unit` to divide into `value` and
-        // tabulate the lowest one for each of the possible values.
-        for i in 1..=value {
-            choices[i] = units
-                .iter()
-                .map(|u| (u.value() as usize, u))
-                // Only values that fit into `i`.
-                .filter(|(v, _)| i >= *v)
-                // Number of steps required to divide into `i`.
-                .map(|(v, u)| choices[i - v].map_or_else(|| (1, *u), |(v, _)| (v + 1, *u)))
-                // Minimum number of steps.
-                .min_by_key(|x| x.0);
-        }
-
-        // Distribute `value` into `units` in the least number of steps possible,
-        // counting each unit as it's used.
-        let mut rem = value;
+        let mut units = units.to_vec();
+        units.sort();
+        units.dedup();
+        let largest = match units.last() {
+            Some(largest) => *largest,
+            None => return HashMap::new(),
+        };
+        let divisible = if units.len() == Self::variants().len() {
+            Self::divisible()
+        } else {
+            each_divides(&units)
+        };
+        if divisible {
+            return greedy(&units, value);
+        }
+
+        // The fewest units never need more of a smaller unit than could be
+        // swapped for some of the largest, so anything past that many can
+        // go straight into the largest unit.
+        let bound: i64 = units
+            .iter()
+            .map(|u| {
+                (largest.value() / u.value().gcd(&largest.value()) - 1).saturating_mul(u.value())
+            })
+            .fold(0, i64::saturating_add);
+        let value = value as i64;
         let mut count = HashMap::new();
-        while let Some((_, unit)) = choices[rem] {
-            rem -= unit.value() as usize;
-            let counter = count.entry(unit).or_insert(0);
-            *counter += 1;
+        let mut rem = value;
+        if value > bound {
+            let n = Integer::div_ceil(&(value - bound), &largest.value());
+            count.insert(largest, n);
+            rem -= largest.value_from_count(n);
+        }
+        for (unit, n) in fewest(&units, rem as usize) {
+            *count.entry(unit).or_insert(0) += n;
         }
         count
     }
@@ -65,13 +101,116 @@ pub trait Unit:
     }
 }
 
-#[derive(Debug)]
+/// Whether each of the sorted `units` divides the next.
+fn each_divides<T: Unit>(units: &[T]) -> bool {
+    units.windows(2).all(|w| w[1].value() % w[0].value() == 0)
+}
+
+/// Distribute `value` largest unit first. Only gives the fewest units when
+/// each unit divides the next, as with `units` sorted.
+fn greedy<T: Unit>(units: &[T], value: usize) -> HashMap<T, i64> {
+    let mut rem = value as i64;
+    let mut count = HashMap::new();
+    for unit in units.iter().rev() {
+        let n = rem / unit.value();
+        if n > 0 {
+            count.insert(*unit, n);
+            rem -= unit.value_from_count(n);
+        }
+    }
+    count
+}
+
+/// Distribute `value` into the fewest `units`, checking every value up to it.
+fn fewest<T: Unit>(units: &[T], value: usize) -> HashMap<T, i64> {
+    // Allocate space for all possible values that `value` could be distributed into.
+    let mut choices: Vec<Option<(usize, T)>> = Vec::with_capacity(value + 1);
+    choices.resize(value + 1, None);
+
+    // Calculate number of steps required for each `unit` to divide into `value` and
+    // tabulate the lowest one for each of the possible values.
+    for i in 1..=value {
+        choices[i] = units
+            .iter()
+            .map(|u| (u.value() as usize, u))
+            // Only values that fit into `i`.
+            .filter(|(v, _)| i >= *v)
+            // Number of steps required to divide into `i`.
+            .map(|(v, u)| choices[i - v].map_or_else(|| (1, *u), |(v, _)| (v + 1, *u)))
+            // Minimum number of steps.
+            .min_by_key(|x| x.0);
+    }
+
+    // Distribute `value` into `units` in the least number of steps possible,
+    // counting each unit as it's used.
+    let mut rem = value;
+    let mut count = HashMap::new();
+    while let Some((_, unit)) = choices[rem] {
+        rem -= unit.value() as usize;
+        let counter = count.entry(unit).or_insert(0);
+        *counter += 1;
+    }
+    count
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CountError {
-    UnitNotFound,
-    NotEnough,
-    InvalidValue,
+    /// A unit that isn't counted, or text that doesn't name one.
+    UnitNotFound(String),
+    /// More was asked for than there is, both counted in `unit`.
+    NotEnough {
+        unit: &'static str,
+        requested: i64,
+        available: i64,
+    },
+    /// Text that couldn't be read as an amount, and why.
+    InvalidValue { text: String, reason: String },
+}
+
+impl CountError {
+    pub fn invalid(text: &str, reason: impl ToString) -> Self {
+        CountError::InvalidValue {
+            text: text.trim().to_string(),
+            reason: reason.to_string(),
+        }
+    }
+
+    /// How much more was needed, for `NotEnough`.
+    pub fn shortfall(&self) -> Option<i64> {
+        match self {
+            CountError::NotEnough {
+                requested,
+                available,
+                ..
+            } => Some(requested - available),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for CountError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CountError::UnitNotFound(unit) => write!(f, "unknown unit \"{}\"", unit),
+            CountError::NotEnough {
+                unit,
+                requested,
+                available,
+            } => write!(
+                f,
+                "not enough, needed {} {} but only {} {} available",
+                requested, unit, available, unit
+            ),
+            CountError::InvalidValue { text, reason } => {
+                write!(f, "\"{}\" isn't a valid amount: {}", text, reason)
+            }
+        }
+    }
 }
 
+impl Error for CountError {}
+
+#[derive(Debug, Clone)]
 pub struct UnitCounter<T>
 where
     T: Unit,
@@ -96,18 +235,91 @@ where
         Self { count, units }
     }
 
+    /// Run `op`, putting every count back as it was if it fails.
+    pub fn atomic<R, F>(&mut self, op: F) -> Result<R, CountError>
+    where
+        F: FnOnce(&mut Self) -> Result<R, CountError>,
+    {
+        let saved = self.count.clone();
+        let result = op(self);
+        if result.is_err() {
+            self.count = saved;
+        }
+        result
+    }
+
     pub fn add(&mut self, value: i64) -> Result<(), CountError> {
         let count = T::distribute_from(&self.units, value.abs() as usize);
-        for (k, v) in count.into_iter() {
-            self.add_units(v * value.signum(), &k)?;
-        }
-        Ok(())
+        self.atomic(|counter| {
+            for (k, v) in count.into_iter() {
+                counter.add_units(v * value.signum(), &k)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Total value of every unit, counted in the base unit.
+    pub fn total(&self) -> i64 {
+        self.count
+            .iter()
+            .map(|(unit, count)| unit.value_from_count(*count))
+            .fold(0, i64::saturating_add)
+    }
+
+    /// Total value counted in `unit`, with the remainder in the base unit.
+    pub fn total_in(&self, unit: &T) -> (i64, i64) {
+        Integer::div_mod_floor(&self.total(), &unit.value())
+    }
+
+    /// The same total expressed in as few of `units` as possible, with
+    /// whatever they can't express left over in the base unit.
+    pub fn convert_to(&self, units: &[T]) -> (Self, i64) {
+        let mut counter = Self::new_with_units(units);
+        counter
+            .add(self.total())
+            .expect("Counter has every unit it distributes to");
+        let rem = self.total() - counter.total();
+        (counter, rem)
     }
 
+    /// Subtract `value` without borrowing, which may leave negative counts.
+    /// See `try_sub`.
     pub fn sub(&mut self, value: i64) -> Result<(), CountError> {
         self.add(-value)
     }
 
+    /// Subtract `value`, borrowing from larger units as needed.
+    ///
+    /// The counter is left normalized, as after `redistribute`: counts that
+    /// fill a larger unit carry over into it, so paying 1 sp out of 150 cp
+    /// leaves 1 gp 4 sp. If there isn't enough the counter is left untouched
+    /// and the error tells how much was asked for and how much there was.
+    /// Units that can't count what's left, such as 2 taken from a Three
+    /// with only Twos and Threes, fail with `InvalidValue` instead.
+    pub fn try_sub(&mut self, value: i64) -> Result<(), CountError> {
+        let total = self.total();
+        match self.units.first() {
+            Some(smallest) if value > total => {
+                return Err(CountError::NotEnough {
+                    unit: smallest.short(),
+                    requested: Integer::div_ceil(&value, &smallest.value()),
+                    available: Integer::div_floor(&total, &smallest.value()),
+                })
+            }
+            _ => {}
+        }
+        self.atomic(|counter| {
+            counter.sub(value)?;
+            // There's enough, so only a rest the units can't count is left.
+            counter.redistribute().map_err(|_| {
+                CountError::invalid(
+                    &value.to_string(),
+                    "the rest can't be counted in these units",
+                )
+            })
+        })
+    }
+
     pub fn add_units(&mut self, count: i64, unit: &T) -> Result<(), CountError> {
         self.get_mut_count(unit)
             .map(|v| *v = v.saturating_add(count))
@@ -120,49 +332,103 @@ where
     pub fn get_count(&self, unit: &T) -> Result<i64, CountError> {
         self.count
             .get(&unit)
-            .map_or_else(|| Err(CountError::UnitNotFound), |v| Ok(*v))
+            .copied()
+            .ok_or_else(|| CountError::UnitNotFound(unit.name().to_string()))
     }
 
     fn get_mut_count(&mut self, unit: &T) -> Result<&mut i64, CountError> {
         self.count
             .get_mut(unit)
-            .map_or_else(|| Err(CountError::UnitNotFound), |v| Ok(v))
+            .ok_or_else(|| CountError::UnitNotFound(unit.name().to_string()))
     }
 
     pub fn set_units(&mut self, count: i64, unit: &T) -> Result<(), CountError> {
         self.get_mut_count(unit).map(|v| *v = count)
     }
 
+    /// Carry counts over into larger units, borrowing from them to cover
+    /// any negative counts.
+    ///
+    /// When a unit doesn't divide the next, counts carry over in groups
+    /// worth the least common multiple of both, e.g. with values 2 and 3
+    /// every 3 of the first become 2 of the second. Whatever doesn't fill
+    /// a group stays in the smaller unit. Borrowing a whole group can take
+    /// more than is held, e.g. one Two owed against one Three, so then the
+    /// total is spread over the units again instead.
+    ///
+    /// Counts are left untouched if the larger units can't cover it all.
     pub fn redistribute(&mut self) -> Result<(), CountError> {
-        // Assuming `self.units` is sorted
+        // `self.units` is sorted on creation.
         let units = self.units.clone();
+        if units.is_empty() {
+            return Ok(());
+        }
+        let shortfall = self.shortfall();
+        if self
+            .atomic(|counter| counter.carry(&units, shortfall.clone()))
+            .is_ok()
+        {
+            return Ok(());
+        }
+        let total = self.total();
+        let spread = T::distribute_from(&units, total.max(0) as usize);
+        let spread_total = spread
+            .iter()
+            .map(|(unit, count)| unit.value_from_count(*count))
+            .fold(0, i64::saturating_add);
+        if total < 0 || spread_total != total {
+            return Err(shortfall);
+        }
+        self.atomic(|counter| {
+            for unit in units.iter() {
+                counter.set_units(spread.get(unit).copied().unwrap_or(0), unit)?;
+            }
+            counter.carry(&units, shortfall)
+        })
+    }
+
+    fn carry(&mut self, units: &[T], shortfall: CountError) -> Result<(), CountError> {
         for (i, unit) in units.iter().enumerate() {
             let count = self.get_count(&unit)?;
-            let result = units.get(i + 1).map(|next| {
-                let total = unit.value_from_count(count);
-                // Calculate how many of `next` units we need to either
-                // steal (negative) or add (positive).
-                let (quo, rem) = total.div_rem(&next.value());
-                // Stealing with remainder will need one extra to cover it.
-                let next_count = if rem.is_negative() { quo - 1 } else { quo };
-                (next_count, next)
-            });
-            match result {
-                Some((next_count, next)) if next_count != 0 => {
-                    self.add_units(next_count, &next)?;
-                    self.sub_units(next.value_from_count(next_count) / unit.value(), &unit)?;
+            match units.get(i + 1) {
+                Some(next) => {
+                    let group = unit.value().lcm(&next.value());
+                    let (per_unit, per_next) = (group / unit.value(), group / next.value());
+                    // Negative counts borrow whole groups from `next`.
+                    let groups = Integer::div_floor(&count, &per_unit);
+                    if groups != 0 {
+                        self.add_units(groups * per_next, &next)?;
+                        self.sub_units(groups * per_unit, &unit)?;
+                    }
                 }
                 // Last unit, can't steal anymore.
-                None if count.is_negative() => {
-                    self.reset(&units)?;
-                    return Err(CountError::NotEnough);
-                }
-                _ => {}
+                None if count.is_negative() => return Err(shortfall),
+                None => {}
             };
         }
         Ok(())
     }
 
+    /// Error for the counts going negative, counted in the smallest unit:
+    /// everything owed against everything held.
+    fn shortfall(&self) -> CountError {
+        let smallest = self.units[0];
+        let (mut requested, mut available) = (0, 0);
+        for (unit, count) in self.count.iter() {
+            let value = unit.value_from_count(count.abs()) / smallest.value();
+            if count.is_negative() {
+                requested += value;
+            } else {
+                available += value;
+            }
+        }
+        CountError::NotEnough {
+            unit: smallest.short(),
+            requested,
+            available,
+        }
+    }
+
     /// Set count for all `units` to 0.
     pub fn reset(&mut self, units: &[T]) -> Result<(), CountError> {
         for unit in units.iter() {
@@ -179,7 +445,47 @@ where
                 self.add_units(count, unit)
             }
             Ok(count) => self.set_units(count, unit),
-            Err(_) => Err(CountError::InvalidValue),
+            Err(e) => Err(CountError::invalid(value, e)),
+        }
+    }
+}
+
+/// Counters compare by total value, so 10 sp equals 1 gp.
+impl<T: Unit> PartialEq for UnitCounter<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.total() == other.total()
+    }
+}
+
+impl<T: Unit> Eq for UnitCounter<T> {}
+
+impl<T: Unit> PartialOrd for UnitCounter<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Unit> Ord for UnitCounter<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.total().cmp(&other.total())
+    }
+}
+
+impl<T: Unit> fmt::Display for UnitCounter<T> {
+    /// Counts largest unit first, skipping empty ones, e.g. "3 gp 4 sp".
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text: Vec<String> = self
+            .units
+            .iter()
+            .rev()
+            .filter_map(|unit| match self.count.get(unit) {
+                Some(&count) if count != 0 => Some(format!("{} {}", count, unit.short())),
+                _ => None,
+            })
+            .collect();
+        match self.units.first() {
+            Some(smallest) if text.is_empty() => write!(f, "0 {}", smallest.short()),
+            _ => write!(f, "{}", text.join(" ")),
         }
     }
 }
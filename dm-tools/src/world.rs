@@ -1,11 +1,206 @@
-use super::time::Time;
+use rand::Rng;
+use std::error::Error;
+use std::fmt;
+
+use super::character::Character;
+use super::inventory::Inventory;
+use super::resource::Rest;
+use super::time::{Category, Event, Scheduler, Time};
+
+/// Name of the timeline a new world starts on.
+pub const MAIN_TIMELINE: &str = "Main";
 
 pub struct World {
-    pub time: Time,
+    pub scheduler: Scheduler,
+    pub party: Vec<Character>,
+    /// Items carried for the whole party.
+    pub inventory: Inventory,
+    /// Name of the timeline `scheduler` keeps time and events for.
+    pub timeline: String,
+    /// The other timelines, set aside until switched to.
+    pub timelines: Vec<Timeline>,
+}
+
+/// A separate track of time, e.g. for half of a split party or downtime,
+/// with its own events.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timeline {
+    pub name: String,
+    pub scheduler: Scheduler,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TimelineError {
+    Exists(String),
+    NotFound(String),
+}
+
+impl fmt::Display for TimelineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TimelineError::Exists(name) => write!(f, "there's already a timeline \"{}\"", name),
+            TimelineError::NotFound(name) => write!(f, "no other timeline is named \"{}\"", name),
+        }
+    }
+}
+
+impl Error for TimelineError {}
+
+/// What happened while the party rested.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RestReport {
+    pub rest: Rest,
+    /// The event that cut the rest short, if any. Nobody recovers anything
+    /// from an interrupted rest.
+    pub interrupted: Option<Event>,
+    /// Hit dice rolled by each character, in party order.
+    pub hit_dice: Vec<(String, Vec<i64>)>,
+    /// Events that ended while resting.
+    pub expired: Vec<Event>,
 }
 
 impl World {
     pub fn new() -> Self {
-        Self { time: Time::new() }
+        Self {
+            scheduler: Scheduler::new(),
+            party: Vec::new(),
+            inventory: Inventory::new(),
+            timeline: MAIN_TIMELINE.to_string(),
+            timelines: Vec::new(),
+        }
+    }
+
+    pub fn time(&self) -> &Time {
+        &self.scheduler.time
+    }
+
+    /// Move time forward, restoring resources for the party at each dawn.
+    /// Returns the scheduled events that started along the way.
+    ///
+    /// The party is shared between timelines, so only a dawn that no
+    /// timeline has got to yet restores anything.
+    pub fn advance(&mut self, duration: &Time) -> Vec<Event> {
+        let from = self
+            .timelines
+            .iter()
+            .map(|t| t.scheduler.time.clone())
+            .fold(self.scheduler.time.clone(), Time::max);
+        let events = self.scheduler.advance(duration);
+        if from.dawns_until(&self.scheduler.time) > 0 {
+            for character in self.party.iter_mut() {
+                character.resources.dawn();
+            }
+        }
+        events
+    }
+
+    /// Start a countdown called `name` that finishes `duration` from now,
+    /// returning its event id. It starts like any event once time gets there.
+    pub fn start_timer(&mut self, name: &str, duration: &Time) -> u64 {
+        let done = Time {
+            value: self.scheduler.time.value + duration.value,
+        };
+        self.scheduler
+            .schedule(name, Category::Timer, done.clone(), done)
+    }
+
+    /// Split off a timeline called `name` at the current time. Events
+    /// scheduled so far happen on both, anything scheduled later only on
+    /// the timeline it was scheduled on.
+    pub fn diverge(&mut self, name: &str) -> Result<(), TimelineError> {
+        if name == self.timeline || self.timelines.iter().any(|t| t.name == name) {
+            return Err(TimelineError::Exists(name.to_string()));
+        }
+        self.timelines.push(Timeline {
+            name: name.to_string(),
+            scheduler: self.scheduler.clone(),
+        });
+        Ok(())
+    }
+
+    /// Play on timeline `name`, setting the current one aside. Switching
+    /// doesn't pass any time, so nothing starts and nobody recovers.
+    pub fn switch_to(&mut self, name: &str) -> Result<(), TimelineError> {
+        let other = self
+            .timelines
+            .iter_mut()
+            .find(|t| t.name == name)
+            .ok_or_else(|| TimelineError::NotFound(name.to_string()))?;
+        std::mem::swap(&mut other.name, &mut self.timeline);
+        std::mem::swap(&mut other.scheduler, &mut self.scheduler);
+        Ok(())
+    }
+
+    /// Bring timeline `name` back into the current one, taking on its
+    /// events. If it got further ahead, time advances up to it, returning
+    /// the events of the current timeline that started.
+    pub fn sync(&mut self, name: &str) -> Result<Vec<Event>, TimelineError> {
+        let i = self
+            .timelines
+            .iter()
+            .position(|t| t.name == name)
+            .ok_or_else(|| TimelineError::NotFound(name.to_string()))?;
+        let behind = self.timelines[i].scheduler.time.value - self.scheduler.time.value;
+        // Advance while `name` is still around, as it's seen those dawns.
+        let started = if behind > 0 {
+            self.advance(&Time { value: behind })
+        } else {
+            Vec::new()
+        };
+        let other = self.timelines.remove(i);
+        self.scheduler.merge(&other.scheduler);
+        Ok(started)
+    }
+
+    /// Have the whole party take `rest`, advancing time by its duration.
+    ///
+    /// On a short rest each character spends as many hit dice as given for
+    /// them in `hit_dice`, in party order. If a scheduled event starts during
+    /// the rest, time only advances up to it and the rest is interrupted.
+    pub fn rest<R: Rng>(&mut self, rest: Rest, hit_dice: &[u32], rng: &mut R) -> RestReport {
+        let from = self.scheduler.time.clone();
+        let until = Time {
+            value: from.value + rest.duration().value,
+        };
+        let interrupted = self
+            .scheduler
+            .events()
+            .iter()
+            .filter(|e| e.start > from && e.start < until)
+            .min_by_key(|e| e.start.clone())
+            .cloned();
+        let end = interrupted.as_ref().map_or(until, |e| e.start.clone());
+        self.advance(&Time {
+            value: end.value - from.value,
+        });
+
+        let expired = self
+            .scheduler
+            .events()
+            .iter()
+            .filter(|e| e.end >= from && e.end < end)
+            .cloned()
+            .collect();
+        let mut report = RestReport {
+            rest,
+            interrupted,
+            hit_dice: Vec::new(),
+            expired,
+        };
+        if report.interrupted.is_some() {
+            return report;
+        }
+
+        for (i, character) in self.party.iter_mut().enumerate() {
+            let rolls = match rest {
+                Rest::Short => character.short_rest(hit_dice.get(i).copied().unwrap_or(0), rng),
+                Rest::Long => {
+                    character.long_rest();
+                    Vec::new()
+                }
+            };
+            report.hit_dice.push((character.name.clone(), rolls));
+        }
+        report
     }
 }
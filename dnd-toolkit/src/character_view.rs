@@ -0,0 +1,931 @@
+use gtk::{
+    BinExt, ButtonExt, Cast, ComboBoxExt, ComboBoxTextExt, ContainerExt, EditableSignals, EntryExt,
+    GridExt, LabelExt, ListBoxExt, ListBoxRowExt, OrientableExt, ScrolledWindowExt,
+    ToggleButtonExt, WidgetExt,
+};
+use relm::{connect, connect_stream, Relm, Widget};
+use relm_derive::{widget, Msg};
+use std::cell::{RefCell, RefMut};
+use std::rc::Rc;
+use strum::AsStaticRef;
+
+use dm_tools::character::{Ability, Character, Skill, MAX_EXHAUSTION};
+use dm_tools::coin::{format_coins, Coin};
+use dm_tools::inventory::{EncumbranceRule, Item};
+use dm_tools::level::{slot_name, xp_for_next_level, HitPoints, LevelUp, Progressions};
+use dm_tools::resource::{Recovery, Resource, Rest};
+use dm_tools::time::Event;
+use dm_tools::ui::count::UnitCounterMsg::Changed;
+use dm_tools::ui::count::{CounterOptions, UnitCounterMsg, UnitCounterView};
+use dm_tools::ui::text::Markup;
+use dm_tools::ui::view::Header;
+use dm_tools::unit::UnitCounter;
+use dm_tools::world::World;
+
+/// Coins items are priced in.
+const ITEM_COINS: [Coin; 3] = [Coin::Copper, Coin::Silver, Coin::Gold];
+
+const RECOVERIES: [Recovery; 5] = [
+    Recovery::ShortRest,
+    Recovery::LongRest,
+    Recovery::Dawn,
+    Recovery::Recharge(5),
+    Recovery::Recharge(6),
+];
+
+/// Widgets built per ability and skill, kept to refresh them on selection.
+struct Fields {
+    abilities: Vec<(Ability, gtk::Entry, gtk::Label)>,
+    saves: Vec<(Ability, gtk::CheckButton)>,
+    skills: Vec<(Skill, gtk::CheckButton)>,
+}
+
+pub struct Model {
+    relm: Relm<CharacterView>,
+    world: Rc<RefCell<World>>,
+    selected: Option<usize>,
+    fields: Fields,
+    rule: EncumbranceRule,
+    /// Hit dice each character spends on the next short rest, in party order.
+    spend: Vec<u32>,
+    progressions: Progressions,
+    /// Value of the next item added to the inventory.
+    item_value: UnitCounter<Coin>,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Add,
+    Remove,
+    Select(Option<i32>),
+    Edit,
+    SetAbility(Ability),
+    ToggleSave(Ability),
+    ToggleSkill(Skill),
+    AddItem,
+    ItemValue(UnitCounter<Coin>),
+    RemoveItem,
+    ToggleVariant,
+    AddResource,
+    SpendResource,
+    RemoveResource,
+    Rest(Rest),
+    /// Time was advanced elsewhere, with these events starting.
+    Advanced(Vec<Event>),
+    /// Emitted after resting moved time forward.
+    TimeChanged,
+    Recharge,
+    AwardXp,
+    AwardMilestone,
+    LevelUp,
+}
+
+#[widget]
+impl Widget for CharacterView {
+    fn model(relm: &Relm<Self>, world: Rc<RefCell<World>>) -> Model {
+        Model {
+            relm: relm.clone(),
+            world,
+            selected: None,
+            fields: Fields {
+                abilities: Vec::new(),
+                saves: Vec::new(),
+                skills: Vec::new(),
+            },
+            rule: EncumbranceRule::Standard,
+            spend: Vec::new(),
+            progressions: Progressions::srd(),
+            item_value: UnitCounter::new_with_units(&ITEM_COINS),
+        }
+    }
+
+    fn subscriptions(&mut self, relm: &Relm<Self>) {
+        for (row, ability) in Ability::variants().iter().cloned().enumerate() {
+            let row = row as i32;
+            let label = gtk::Label::new(Some(ability.short()));
+            let entry = gtk::Entry::new();
+            entry.set_width_chars(3);
+            let modifier = gtk::Label::new(None);
+            let save = gtk::CheckButton::new_with_label("Save");
+            self.abilities.attach(&label, 0, row, 1, 1);
+            self.abilities.attach(&entry, 1, row, 1, 1);
+            self.abilities.attach(&modifier, 2, row, 1, 1);
+            self.abilities.attach(&save, 3, row, 1, 1);
+            connect!(relm, entry, connect_changed(_), Msg::SetAbility(ability));
+            connect!(relm, save, connect_toggled(_), Msg::ToggleSave(ability));
+            self.model.fields.abilities.push((ability, entry, modifier));
+            self.model.fields.saves.push((ability, save));
+        }
+        for (i, skill) in Skill::variants().iter().cloned().enumerate() {
+            let check = gtk::CheckButton::new_with_label("");
+            self.skills
+                .attach(&check, (i % 2) as i32, (i / 2) as i32, 1, 1);
+            connect!(relm, check, connect_toggled(_), Msg::ToggleSkill(skill));
+            self.model.fields.skills.push((skill, check));
+        }
+        for recovery in RECOVERIES.iter() {
+            self.resource_recovery.append_text(&recovery.to_string());
+        }
+        self.resource_recovery.set_active(Some(0));
+        self.abilities.show_all();
+        self.skills.show_all();
+        self.update_time();
+        self.load();
+    }
+
+    // Handlers read the widgets rather than toggling, so the signals emitted
+    // while loading a character into the editor leave it unchanged.
+    fn update(&mut self, event: Msg) {
+        match event {
+            Msg::Add => {
+                let len = {
+                    let mut world = self.model.world.borrow_mut();
+                    let number = world.party.len() + 1;
+                    let character = Character::new(&format!("Character {}", number), "", 1);
+                    world.party.push(character);
+                    world.party.len()
+                };
+                self.model.spend.push(0);
+                self.update_list();
+                self.select(Some(len - 1));
+            }
+            Msg::Remove => {
+                if let Some(i) = self.model.selected {
+                    self.model.world.borrow_mut().party.remove(i);
+                    self.model.spend.remove(i);
+                    self.update_list();
+                    let len = self.model.world.borrow().party.len();
+                    self.select(if len == 0 { None } else { Some(i.min(len - 1)) });
+                }
+            }
+            Msg::Select(index) => {
+                self.model.selected = index.map(|i| i as usize);
+                self.load();
+            }
+            Msg::Edit => {
+                let name = self.selected_mut_from_entries().map(|c| c.name.clone());
+                if let (Some(name), Some(i)) = (name, self.model.selected) {
+                    self.rename_row(i, &name);
+                }
+                self.update_derived();
+            }
+            Msg::SetAbility(ability) => {
+                let score = self
+                    .model
+                    .fields
+                    .abilities
+                    .iter()
+                    .find(|(a, _, _)| *a == ability)
+                    .and_then(|(_, entry, _)| entry.get_text())
+                    .and_then(|text| text.trim().parse::<i64>().ok());
+                if let (Some(mut character), Some(score)) = (self.selected_mut(), score) {
+                    character.abilities.set(ability, score);
+                }
+                self.update_derived();
+            }
+            Msg::ToggleSave(ability) => {
+                let active = self
+                    .model
+                    .fields
+                    .saves
+                    .iter()
+                    .any(|(a, check)| *a == ability && check.get_active());
+                if let Some(mut character) = self.selected_mut() {
+                    if active {
+                        character.saves.insert(ability);
+                    } else {
+                        character.saves.remove(&ability);
+                    }
+                }
+                self.update_derived();
+            }
+            Msg::ToggleSkill(skill) => {
+                let active = self
+                    .model
+                    .fields
+                    .skills
+                    .iter()
+                    .any(|(s, check)| *s == skill && check.get_active());
+                if let Some(mut character) = self.selected_mut() {
+                    if active {
+                        character.skills.insert(skill);
+                    } else {
+                        character.skills.remove(&skill);
+                    }
+                }
+                self.update_derived();
+            }
+            Msg::AddItem => {
+                if let Some(item) = self.item_from_entries() {
+                    let container = entry_text(&self.item_container);
+                    if let Some(mut character) = self.selected_mut() {
+                        let inventory = &mut character.inventory;
+                        if container.is_empty() {
+                            inventory.push(item);
+                        } else if let Err(item) = inventory.push_into(&container, item) {
+                            inventory.push(item);
+                        }
+                    }
+                    self.item_name.set_text("");
+                    self.model.item_value = UnitCounter::new_with_units(&ITEM_COINS);
+                    self.item_value
+                        .stream()
+                        .emit(UnitCounterMsg::Set(self.model.item_value.clone()));
+                }
+                self.update_derived();
+            }
+            Msg::ItemValue(value) => self.model.item_value = value,
+            Msg::RemoveItem => {
+                let name = entry_text(&self.item_name);
+                if let Some(mut character) = self.selected_mut() {
+                    character.inventory.remove(&name);
+                }
+                self.update_derived();
+            }
+            Msg::ToggleVariant => {
+                self.model.rule = if self.variant.get_active() {
+                    EncumbranceRule::Variant
+                } else {
+                    EncumbranceRule::Standard
+                };
+                self.update_derived();
+            }
+            Msg::AddResource => {
+                let name = entry_text(&self.resource_name);
+                let max = entry_text(&self.resource_max).parse::<u32>().ok();
+                let recovery = self
+                    .resource_recovery
+                    .get_active()
+                    .and_then(|i| RECOVERIES.get(i as usize))
+                    .copied();
+                if let (false, Some(max), Some(recovery), Some(mut character)) =
+                    (name.is_empty(), max, recovery, self.selected_mut())
+                {
+                    character.resources.remove(&name);
+                    character
+                        .resources
+                        .push(Resource::new(&name, max, recovery));
+                }
+                self.update_derived();
+            }
+            Msg::SpendResource => {
+                let name = entry_text(&self.resource_name);
+                if let Some(mut character) = self.selected_mut() {
+                    character.resources.spend(&name, 1);
+                }
+                self.update_derived();
+            }
+            Msg::RemoveResource => {
+                let name = entry_text(&self.resource_name);
+                if let Some(mut character) = self.selected_mut() {
+                    character.resources.remove(&name);
+                }
+                self.update_derived();
+            }
+            Msg::Rest(rest) => {
+                let report = self.model.world.borrow_mut().rest(
+                    rest,
+                    &self.model.spend,
+                    &mut rand::thread_rng(),
+                );
+                let mut lines = vec![match report.interrupted {
+                    Some(ref event) => format!(
+                        "{} rest interrupted at {} by {}",
+                        rest.as_static(),
+                        event.start,
+                        event.name
+                    ),
+                    None => format!("{} rest finished", rest.as_static()),
+                }];
+                for (name, rolls) in report.hit_dice.iter().filter(|(_, r)| !r.is_empty()) {
+                    let rolls: Vec<String> = rolls.iter().map(i64::to_string).collect();
+                    lines.push(format!("{} rolled {}", name, rolls.join(", ")));
+                }
+                for event in report.expired.iter() {
+                    lines.push(format!("{} ended", event.name));
+                }
+                self.rest_report.set_text(&lines.join("\n"));
+                self.update_time();
+                self.load();
+                self.model.relm.stream().emit(Msg::TimeChanged);
+            }
+            Msg::Advanced(started) => {
+                let time = self.model.world.borrow().time().clone();
+                let mut lines = vec![format!("It's now {}", time)];
+                for event in started.iter() {
+                    lines.push(format!("{} started", event.name));
+                }
+                self.rest_report.set_text(&lines.join("\n"));
+                self.update_time();
+                self.load();
+            }
+            // For the parent.
+            Msg::TimeChanged => {}
+            Msg::Recharge => {
+                let mut rng = rand::thread_rng();
+                for character in self.model.world.borrow_mut().party.iter_mut() {
+                    character.resources.roll_recharge(&mut rng);
+                }
+                self.update_derived();
+            }
+            Msg::AwardXp => {
+                if let Ok(xp) = entry_text(&self.xp).parse::<i64>() {
+                    if let Some(mut character) = self.selected_mut() {
+                        character.award_xp(xp);
+                    }
+                    self.xp.set_text("");
+                }
+                self.update_derived();
+            }
+            Msg::AwardMilestone => {
+                let milestone = entry_text(&self.milestone);
+                if !milestone.is_empty() {
+                    if let Some(mut character) = self.selected_mut() {
+                        character.award_milestone(&milestone);
+                    }
+                    self.milestone.set_text("");
+                }
+                self.update_derived();
+            }
+            Msg::LevelUp => {
+                let hp = if self.roll_hp.get_active() {
+                    HitPoints::Rolled
+                } else {
+                    HitPoints::Average
+                };
+                let text = match self.selected_mut() {
+                    Some(mut character) => {
+                        let progression = self.model.progressions.find(&character.class);
+                        match character.level_up(hp, progression, &mut rand::thread_rng()) {
+                            Some(level_up) => level_up_text(&level_up),
+                            None => "No level pending".to_string(),
+                        }
+                    }
+                    None => String::new(),
+                };
+                self.level_up_result.set_text(&text);
+                self.load();
+            }
+        }
+    }
+
+    view! {
+        gtk::Box {
+            spacing: 20,
+
+            gtk::Box {
+                orientation: gtk::Orientation::Vertical,
+                spacing: 10,
+                margin_start: 10,
+
+                Header("Party") {},
+                #[name="list"]
+                gtk::ListBox {
+                    vexpand: true,
+                    row_selected(_, row) => Msg::Select(row.as_ref().map(|r| r.get_index())),
+                },
+                gtk::Box {
+                    spacing: 10,
+
+                    gtk::Button {
+                        label: "Add",
+                        clicked => Msg::Add,
+                    },
+                    gtk::Button {
+                        label: "Remove",
+                        clicked => Msg::Remove,
+                    },
+                },
+                Header("Rest") {},
+                #[name="time"]
+                gtk::Label {},
+                gtk::Box {
+                    spacing: 10,
+
+                    gtk::Button {
+                        label: "Short rest",
+                        clicked => Msg::Rest(Rest::Short),
+                    },
+                    gtk::Button {
+                        label: "Long rest",
+                        clicked => Msg::Rest(Rest::Long),
+                    },
+                    gtk::Button {
+                        label: "Roll recharge",
+                        clicked => Msg::Recharge,
+                    },
+                },
+                #[name="rest_report"]
+                gtk::Label {
+                    halign: gtk::Align::Start,
+                },
+            },
+            gtk::ScrolledWindow {
+                hexpand: true,
+                hscrollbar_policy: gtk::PolicyType::Never,
+
+                #[name="editor"]
+                gtk::Box {
+                    orientation: gtk::Orientation::Vertical,
+                    spacing: 10,
+                    margin_end: 10,
+
+                    gtk::Box {
+                        spacing: 10,
+
+                        #[name="name"]
+                        gtk::Entry {
+                            placeholder_text: Some("Name"),
+                            changed => Msg::Edit,
+                        },
+                        #[name="class"]
+                        gtk::Entry {
+                            placeholder_text: Some("Class"),
+                            changed => Msg::Edit,
+                        },
+                        #[name="level"]
+                        gtk::Entry {
+                            placeholder_text: Some("Level"),
+                            width_chars: 3,
+                            changed => Msg::Edit,
+                        },
+                    },
+                    gtk::Box {
+                        spacing: 10,
+
+                        #[name="hp"]
+                        gtk::Entry {
+                            placeholder_text: Some("HP"),
+                            width_chars: 4,
+                            changed => Msg::Edit,
+                        },
+                        #[name="max_hp"]
+                        gtk::Entry {
+                            placeholder_text: Some("Max HP"),
+                            width_chars: 4,
+                            changed => Msg::Edit,
+                        },
+                        #[name="ac"]
+                        gtk::Entry {
+                            placeholder_text: Some("AC"),
+                            width_chars: 3,
+                            changed => Msg::Edit,
+                        },
+                    },
+                    gtk::Box {
+                        spacing: 10,
+
+                        gtk::Label {
+                            text: "Hit die d",
+                        },
+                        #[name="hit_die"]
+                        gtk::Entry {
+                            width_chars: 3,
+                            changed => Msg::Edit,
+                        },
+                        gtk::Label {
+                            text: "Hit dice left",
+                        },
+                        #[name="hit_dice"]
+                        gtk::Entry {
+                            width_chars: 3,
+                            changed => Msg::Edit,
+                        },
+                        gtk::Label {
+                            text: "Spend on short rest",
+                        },
+                        #[name="spend"]
+                        gtk::Entry {
+                            width_chars: 3,
+                            changed => Msg::Edit,
+                        },
+                        gtk::Label {
+                            text: "Exhaustion",
+                        },
+                        #[name="exhaustion"]
+                        gtk::Entry {
+                            width_chars: 3,
+                            changed => Msg::Edit,
+                        },
+                    },
+                    gtk::Box {
+                        spacing: 20,
+
+                        #[name="abilities"]
+                        gtk::Grid {
+                            row_spacing: 5,
+                            column_spacing: 10,
+                        },
+                        #[name="skills"]
+                        gtk::Grid {
+                            row_spacing: 5,
+                            column_spacing: 10,
+                        },
+                    },
+                    #[name="summary"]
+                    gtk::Label {
+                        halign: gtk::Align::Start,
+                        use_markup: true,
+                    },
+                    Header("Advancement") {},
+                    gtk::Box {
+                        spacing: 10,
+
+                        #[name="xp"]
+                        gtk::Entry {
+                            placeholder_text: Some("XP"),
+                            width_chars: 6,
+                            activate => Msg::AwardXp,
+                        },
+                        gtk::Button {
+                            label: "Award XP",
+                            clicked => Msg::AwardXp,
+                        },
+                        #[name="milestone"]
+                        gtk::Entry {
+                            placeholder_text: Some("Milestone"),
+                            activate => Msg::AwardMilestone,
+                        },
+                        gtk::Button {
+                            label: "Milestone",
+                            clicked => Msg::AwardMilestone,
+                        },
+                        #[name="roll_hp"]
+                        gtk::CheckButton {
+                            label: "Roll HP",
+                        },
+                        gtk::Button {
+                            label: "Level up",
+                            clicked => Msg::LevelUp,
+                        },
+                    },
+                    #[name="advancement"]
+                    gtk::Label {
+                        halign: gtk::Align::Start,
+                    },
+                    #[name="level_up_result"]
+                    gtk::Label {
+                        halign: gtk::Align::Start,
+                    },
+                    Header("Inventory") {},
+                    gtk::Box {
+                        spacing: 10,
+
+                        #[name="item_name"]
+                        gtk::Entry {
+                            placeholder_text: Some("Item"),
+                            activate => Msg::AddItem,
+                        },
+                        #[name="item_quantity"]
+                        gtk::Entry {
+                            placeholder_text: Some("Qty"),
+                            width_chars: 3,
+                        },
+                        #[name="item_weight"]
+                        gtk::Entry {
+                            placeholder_text: Some("lb"),
+                            width_chars: 4,
+                        },
+                        #[name="item_container"]
+                        gtk::Entry {
+                            placeholder_text: Some("Inside"),
+                        },
+                        #[name="item_weightless"]
+                        gtk::CheckButton {
+                            label: "Weightless contents",
+                        },
+                        gtk::Button {
+                            label: "Add",
+                            clicked => Msg::AddItem,
+                        },
+                        gtk::Button {
+                            label: "Remove",
+                            clicked => Msg::RemoveItem,
+                        },
+                    },
+                    #[name="item_value"]
+                    UnitCounterView<Coin>(CounterOptions::with_units(&ITEM_COINS)) {
+                        Changed(ref value) => Msg::ItemValue(value.clone()),
+                    },
+                    #[name="variant"]
+                    gtk::CheckButton {
+                        label: "Variant encumbrance",
+                        toggled => Msg::ToggleVariant,
+                    },
+                    #[name="inventory"]
+                    gtk::Label {
+                        halign: gtk::Align::Start,
+                        selectable: true,
+                    },
+                    Header("Resources") {},
+                    gtk::Box {
+                        spacing: 10,
+
+                        #[name="resource_name"]
+                        gtk::Entry {
+                            placeholder_text: Some("Resource"),
+                            activate => Msg::SpendResource,
+                        },
+                        #[name="resource_max"]
+                        gtk::Entry {
+                            placeholder_text: Some("Max"),
+                            width_chars: 3,
+                            activate => Msg::AddResource,
+                        },
+                        #[name="resource_recovery"]
+                        gtk::ComboBoxText {},
+                        gtk::Button {
+                            label: "Add",
+                            clicked => Msg::AddResource,
+                        },
+                        gtk::Button {
+                            label: "Spend",
+                            clicked => Msg::SpendResource,
+                        },
+                        gtk::Button {
+                            label: "Remove",
+                            clicked => Msg::RemoveResource,
+                        },
+                    },
+                    #[name="resources"]
+                    gtk::Label {
+                        halign: gtk::Align::Start,
+                        selectable: true,
+                    },
+                },
+            },
+        }
+    }
+}
+
+fn entry_text(entry: &gtk::Entry) -> String {
+    entry
+        .get_text()
+        .map(|t| t.trim().to_string())
+        .unwrap_or_default()
+}
+
+fn signed(value: i64) -> String {
+    format!("{:+}", value)
+}
+
+fn level_up_text(level_up: &LevelUp) -> String {
+    let mut text = format!(
+        "Reached level {}: +{} HP, proficiency {}",
+        level_up.level,
+        level_up.hp,
+        signed(level_up.proficiency_bonus)
+    );
+    for (level, count) in level_up.slots.iter() {
+        text.push_str(&format!(", +{} {}", count, slot_name(*level)));
+    }
+    text
+}
+
+/// One line per item, indented by how deeply it's nested.
+fn item_lines(items: &[Item], depth: usize, lines: &mut Vec<String>) {
+    for item in items.iter() {
+        lines.push(format!(
+            "{}{} x{}  {} lb  {}",
+            "    ".repeat(depth),
+            item.name,
+            item.quantity,
+            item.total_weight(),
+            format_coins(&item.total_value())
+        ));
+        item_lines(&item.contents, depth + 1, lines);
+    }
+}
+
+impl CharacterView {
+    fn selected_mut(&self) -> Option<RefMut<Character>> {
+        let i = self.model.selected?;
+        let world = self.model.world.borrow_mut();
+        if i >= world.party.len() {
+            return None;
+        }
+        Some(RefMut::map(world, |world| &mut world.party[i]))
+    }
+
+    /// Copy the editor's text fields into the selected character.
+    fn selected_mut_from_entries(&mut self) -> Option<RefMut<Character>> {
+        let name = entry_text(&self.name);
+        let class = entry_text(&self.class);
+        let level = entry_text(&self.level).parse::<u8>().ok();
+        let hp = entry_text(&self.hp).parse::<i64>().ok();
+        let max_hp = entry_text(&self.max_hp).parse::<i64>().ok();
+        let ac = entry_text(&self.ac).parse::<i64>().ok();
+        let hit_die = entry_text(&self.hit_die).parse::<u32>().ok();
+        let hit_dice = entry_text(&self.hit_dice).parse::<u32>().ok();
+        let exhaustion = entry_text(&self.exhaustion).parse::<u8>().ok();
+        if let (Some(i), Ok(spend)) = (self.model.selected, entry_text(&self.spend).parse()) {
+            if let Some(count) = self.model.spend.get_mut(i) {
+                *count = spend;
+            }
+        }
+
+        let mut character = self.selected_mut()?;
+        character.name = name;
+        character.class = class;
+        if let Some(level) = level {
+            character.level = level.clamp(1, 20);
+        }
+        if let Some(hp) = hp {
+            character.hp = hp;
+        }
+        if let Some(max_hp) = max_hp {
+            character.max_hp = max_hp;
+        }
+        if let Some(ac) = ac {
+            character.ac = ac;
+        }
+        if let Some(hit_die) = hit_die.filter(|sides| *sides > 0) {
+            character.hit_die = hit_die;
+        }
+        if let Some(hit_dice) = hit_dice {
+            character.hit_dice = hit_dice.min(u32::from(character.level));
+        }
+        if let Some(exhaustion) = exhaustion {
+            character.exhaustion = exhaustion.min(MAX_EXHAUSTION);
+        }
+        Some(character)
+    }
+
+    fn item_from_entries(&self) -> Option<Item> {
+        let name = entry_text(&self.item_name);
+        if name.is_empty() {
+            return None;
+        }
+        let quantity = entry_text(&self.item_quantity).parse::<u32>().unwrap_or(1);
+        let weight = entry_text(&self.item_weight).parse::<f64>().unwrap_or(0.0);
+        let mut item = Item::new(&name, quantity, weight);
+        for coin in ITEM_COINS.iter() {
+            let count = self.model.item_value.get_count(coin).unwrap_or(0);
+            if count != 0 {
+                item = item.with_value(count, *coin);
+            }
+        }
+        if self.item_weightless.get_active() {
+            item = item.with_weightless_contents();
+        }
+        Some(item)
+    }
+
+    fn select(&mut self, index: Option<usize>) {
+        match index.and_then(|i| self.list.get_row_at_index(i as i32)) {
+            Some(row) => self.list.select_row(Some(&row)),
+            None => self.list.unselect_all(),
+        }
+        self.model.selected = index;
+        self.load();
+    }
+
+    fn update_list(&mut self) {
+        for child in self.list.get_children().iter() {
+            self.list.remove(child);
+        }
+        for character in self.model.world.borrow().party.iter() {
+            let label = gtk::Label::new(Some(character.name.as_str()));
+            label.set_halign(gtk::Align::Start);
+            self.list.insert(&label, -1);
+        }
+        self.list.show_all();
+    }
+
+    fn rename_row(&self, index: usize, name: &str) {
+        let label = self
+            .list
+            .get_row_at_index(index as i32)
+            .and_then(|row| row.get_child())
+            .and_then(|child| child.downcast::<gtk::Label>().ok());
+        if let Some(label) = label {
+            label.set_text(name);
+        }
+    }
+
+    /// Fill the editor in from the selected character.
+    fn load(&mut self) {
+        let character = self
+            .model
+            .selected
+            .and_then(|i| self.model.world.borrow().party.get(i).cloned());
+        self.editor.set_sensitive(character.is_some());
+        let character = character.unwrap_or_else(|| Character::new("", "", 1));
+
+        self.name.set_text(&character.name);
+        self.class.set_text(&character.class);
+        self.level.set_text(&character.level.to_string());
+        self.hp.set_text(&character.hp.to_string());
+        self.max_hp.set_text(&character.max_hp.to_string());
+        self.ac.set_text(&character.ac.to_string());
+        self.hit_die.set_text(&character.hit_die.to_string());
+        self.hit_dice.set_text(&character.hit_dice.to_string());
+        self.exhaustion.set_text(&character.exhaustion.to_string());
+        let spend = self
+            .model
+            .selected
+            .and_then(|i| self.model.spend.get(i))
+            .copied()
+            .unwrap_or(0);
+        self.spend.set_text(&spend.to_string());
+        for (ability, entry, _) in self.model.fields.abilities.iter() {
+            entry.set_text(&character.abilities.get(*ability).to_string());
+        }
+        for (ability, check) in self.model.fields.saves.iter() {
+            check.set_active(character.saves.contains(ability));
+        }
+        for (skill, check) in self.model.fields.skills.iter() {
+            check.set_active(character.skills.contains(skill));
+        }
+        self.update_derived();
+    }
+
+    /// Refresh modifiers and other values derived from the character.
+    fn update_derived(&mut self) {
+        let world = self.model.world.borrow();
+        let character = match self.model.selected.and_then(|i| world.party.get(i)) {
+            Some(character) => character,
+            None => {
+                self.inventory.set_text("");
+                self.resources.set_text("");
+                self.advancement.set_text("");
+                return self.summary.set_markup("");
+            }
+        };
+        for (ability, _, label) in self.model.fields.abilities.iter() {
+            label.set_text(&signed(character.modifier(*ability)));
+        }
+        for (ability, check) in self.model.fields.saves.iter() {
+            check.set_label(&format!("Save {}", signed(character.save(*ability))));
+        }
+        for (skill, check) in self.model.fields.skills.iter() {
+            check.set_label(&format!(
+                "{} {}",
+                skill.as_static(),
+                signed(character.skill(*skill))
+            ));
+        }
+        let summary = format!(
+            "{}\nLevel {} {}  Proficiency {}  HP {}/{}  AC {}  Passive Perception {}",
+            glib::markup_escape_text(&character.name).markup_bold(),
+            character.level,
+            glib::markup_escape_text(&character.class),
+            signed(character.proficiency_bonus()),
+            character.hp,
+            character.max_hp,
+            character.ac,
+            character.passive_perception(),
+        );
+        self.summary.set_markup(&summary);
+
+        let inventory = &character.inventory;
+        let encumbrance = character.encumbrance(self.model.rule);
+        let mut lines = Vec::new();
+        item_lines(inventory.items(), 0, &mut lines);
+        let speed = match encumbrance.speed_cap() {
+            Some(cap) => format!("speed {} ft at most", cap),
+            None => format!("speed -{} ft", encumbrance.speed_penalty()),
+        };
+        lines.push(format!(
+            "Carrying {} of {} lb, {} ({})  Worth {}",
+            inventory.weight(),
+            character.carrying_capacity(),
+            encumbrance.as_static(),
+            speed,
+            format_coins(&inventory.value())
+        ));
+        self.inventory.set_text(&lines.join("\n"));
+
+        let resources: Vec<String> = character
+            .resources
+            .resources()
+            .iter()
+            .map(|r| format!("{} {}/{} ({})", r.name, r.current, r.max, r.recovery))
+            .collect();
+        self.resources.set_text(&resources.join("\n"));
+
+        let next = match xp_for_next_level(character.level) {
+            Some(xp) => format!("XP {} of {}", character.xp, xp),
+            None => format!("XP {}", character.xp),
+        };
+        let mut advancement = vec![format!(
+            "{}, {} level(s) pending",
+            next,
+            character.pending_levels()
+        )];
+        if !character.milestones.is_empty() {
+            advancement.push(format!("Milestones: {}", character.milestones.join(", ")));
+        }
+        self.advancement.set_text(&advancement.join("\n"));
+    }
+
+    fn update_time(&mut self) {
+        let world = self.model.world.borrow();
+        match world.timelines.len() {
+            0 => self.time.set_text(&world.time().to_string()),
+            _ => self
+                .time
+                .set_text(&format!("{} on {}", world.time(), world.timeline)),
+        }
+    }
+}
@@ -0,0 +1,546 @@
+use gtk::{
+    ButtonExt, ComboBoxExtManual, ComboBoxTextExt, DialogExt, EditableSignals, EntryExt,
+    FileChooserExt, LabelExt, OrientableExt, ScrolledWindowExt, TextBufferExt, TextViewExt,
+    ToggleButtonExt, WidgetExt,
+};
+use relm::{connect, connect_stream, Relm, Widget};
+use relm_derive::{widget, Msg};
+use std::cell::RefCell;
+use std::fs;
+use std::rc::Rc;
+use strum::AsStaticRef;
+
+use dm_tools::combat::{Combat, Combatant, Condition, Turn, LAIR_INITIATIVE};
+use dm_tools::mob;
+use dm_tools::time::Time;
+use dm_tools::ui::text::Markup;
+use dm_tools::world::World;
+
+pub struct Model {
+    relm: Relm<CombatView>,
+    world: Rc<RefCell<World>>,
+    combat: Combat,
+    /// Combat time already added to the world.
+    elapsed: Time,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Add,
+    NextTurn,
+    End,
+    New,
+    Damage,
+    Heal,
+    ApplyCondition,
+    RemoveCondition,
+    Legendary,
+    GroupInitiative,
+    DamageGroup,
+    Mob,
+    ExportMarkdown,
+    ExportJson,
+    /// Emitted when the combat ends, with the time it took since last ended.
+    Finished(Time),
+}
+
+#[widget]
+impl Widget for CombatView {
+    fn model(relm: &Relm<Self>, world: Rc<RefCell<World>>) -> Model {
+        let combat = Combat::new(world.borrow().time().clone());
+        Model {
+            relm: relm.clone(),
+            world,
+            combat,
+            elapsed: Time::new(),
+        }
+    }
+
+    fn init_view(&mut self) {
+        for condition in Condition::variants().iter() {
+            self.condition.append_text(condition.as_static());
+        }
+        self.condition.set_active(Some(0));
+        self.update_view();
+    }
+
+    fn update(&mut self, event: Msg) {
+        // Indices shift as combatants join, so remember the target by id.
+        let target = self.target().map(|i| self.model.combat.combatants()[i].id);
+        match event {
+            Msg::Add => {
+                let name = entry_text(&self.name);
+                let initiative = entry_text(&self.initiative).parse::<i64>();
+                let hp = entry_text(&self.hp).parse::<i64>();
+                let count = entry_text(&self.count).parse::<usize>().unwrap_or(1);
+                let legendary = entry_text(&self.legendary).parse::<u32>().unwrap_or(0);
+                if count > 1 && (legendary > 0 || self.lair.get_active()) {
+                    return self
+                        .status
+                        .set_text("Add legendary and lair creatures one at a time");
+                }
+                if let (false, Ok(initiative), Ok(hp)) = (name.is_empty(), initiative, hp) {
+                    self.count.set_text("");
+                    self.status.set_text("");
+                    if count > 1 {
+                        self.model.combat.add_group(&name, count, initiative, hp);
+                        self.name.set_text("");
+                        self.update_view();
+                        return self.select_target(target);
+                    }
+                    let mut combatant =
+                        Combatant::new(&name, initiative, hp).with_legendary_actions(legendary);
+                    if self.lair.get_active() {
+                        combatant = combatant.with_lair();
+                    }
+                    self.model.combat.add(combatant);
+                    self.name.set_text("");
+                    self.initiative.set_text("");
+                    self.hp.set_text("");
+                    self.legendary.set_text("");
+                    self.lair.set_active(false);
+                }
+            }
+            Msg::NextTurn => {
+                if !self.model.combat.is_started() {
+                    let now = self.model.world.borrow().time().clone();
+                    self.model.combat.set_start(now);
+                }
+                self.model.combat.next_turn();
+            }
+            Msg::End => {
+                self.model.combat.end();
+                let elapsed = self.model.combat.elapsed();
+                let duration = Time {
+                    value: elapsed.value - self.model.elapsed.value,
+                };
+                self.model.elapsed = elapsed;
+                if duration.value > 0 {
+                    self.model.relm.stream().emit(Msg::Finished(duration));
+                }
+            }
+            Msg::New => {
+                self.model.combat = Combat::new(self.model.world.borrow().time().clone());
+                self.model.elapsed = Time::new();
+            }
+            Msg::Damage | Msg::Heal => {
+                let amount = entry_text(&self.amount).parse::<i64>();
+                if let (Some(target), Ok(amount)) = (self.target(), amount) {
+                    match event {
+                        Msg::Damage => {
+                            let source = self.model.combat.current().map(|c| c.name.clone());
+                            self.model.combat.damage(target, amount, source.as_deref())
+                        }
+                        _ => self.model.combat.heal(target, amount),
+                    }
+                    self.amount.set_text("");
+                }
+            }
+            Msg::ApplyCondition | Msg::RemoveCondition => {
+                let condition = self.condition.get_active_text().and_then(|text| {
+                    Condition::variants()
+                        .iter()
+                        .find(|c| c.as_static() == text.as_str())
+                        .cloned()
+                });
+                if let (Some(target), Some(condition)) = (self.target(), condition) {
+                    match event {
+                        Msg::ApplyCondition => {
+                            let rounds = entry_text(&self.rounds).parse::<u32>().ok();
+                            self.model.combat.apply_condition(target, condition, rounds)
+                        }
+                        _ => self.model.combat.remove_condition(target, condition),
+                    }
+                }
+            }
+            Msg::GroupInitiative => {
+                let enabled = self.group_initiative.get_active();
+                self.model.combat.set_group_initiative(enabled);
+            }
+            Msg::DamageGroup => {
+                let amount = entry_text(&self.amount).parse::<i64>();
+                let group = self
+                    .target()
+                    .and_then(|i| self.model.combat.combatants()[i].group.clone());
+                if let (Some(group), Ok(amount)) = (group, amount) {
+                    let source = self.model.combat.current().map(|c| c.name.clone());
+                    self.model
+                        .combat
+                        .damage_group(&group, amount, source.as_deref());
+                    self.amount.set_text("");
+                }
+            }
+            Msg::Mob => {
+                let attackers = entry_text(&self.mob_attackers).parse::<u32>();
+                let bonus = entry_text(&self.mob_bonus).parse::<i64>();
+                let ac = entry_text(&self.mob_ac).parse::<i64>();
+                let text = match (attackers, bonus, ac) {
+                    (Ok(attackers), Ok(bonus), Ok(ac)) => format!(
+                        "{} hits ({} per hit), {:.1} expected",
+                        mob::table_hits(attackers, ac, bonus),
+                        mob::attackers_per_hit(ac, bonus),
+                        mob::expected_hits(attackers, ac, bonus)
+                    ),
+                    _ => String::new(),
+                };
+                self.mob_result.set_text(&text);
+            }
+            Msg::Legendary => {
+                let cost = match entry_text(&self.legendary_cost).as_str() {
+                    "" => Ok(1),
+                    text => text.parse::<u32>(),
+                };
+                if let (Some(target), Ok(cost)) = (self.target(), cost) {
+                    let status = if self.model.combat.use_legendary(target, cost) {
+                        self.legendary_cost.set_text("");
+                        String::new()
+                    } else {
+                        format!(
+                            "{} can't take a legendary action costing {} now",
+                            self.model.combat.combatants()[target].name,
+                            cost
+                        )
+                    };
+                    self.status.set_text(&status);
+                }
+            }
+            Msg::ExportMarkdown => self.export("combat.md", self.model.combat.log().to_markdown()),
+            Msg::ExportJson => match self.model.combat.log().to_json() {
+                Ok(json) => self.export("combat.json", json),
+                Err(e) => self
+                    .status
+                    .set_text(&format!("Could not write the combat log as JSON: {}", e)),
+            },
+            // For the parent.
+            Msg::Finished(_) => {}
+        }
+        self.update_view();
+        self.select_target(target);
+    }
+
+    view! {
+        gtk::Box {
+            orientation: gtk::Orientation::Vertical,
+            spacing: 10,
+            margin_start: 10,
+            margin_end: 10,
+
+            gtk::Box {
+                spacing: 10,
+
+                #[name="name"]
+                gtk::Entry {
+                    hexpand: true,
+                    placeholder_text: Some("Name"),
+                    activate => Msg::Add,
+                },
+                #[name="initiative"]
+                gtk::Entry {
+                    placeholder_text: Some("Initiative"),
+                    width_chars: 8,
+                    activate => Msg::Add,
+                },
+                #[name="hp"]
+                gtk::Entry {
+                    placeholder_text: Some("HP"),
+                    width_chars: 4,
+                    activate => Msg::Add,
+                },
+                #[name="count"]
+                gtk::Entry {
+                    placeholder_text: Some("Count"),
+                    width_chars: 5,
+                    activate => Msg::Add,
+                },
+                #[name="legendary"]
+                gtk::Entry {
+                    placeholder_text: Some("Legendary"),
+                    width_chars: 8,
+                    activate => Msg::Add,
+                },
+                #[name="lair"]
+                gtk::CheckButton {
+                    label: "Lair",
+                },
+                gtk::Button {
+                    label: "Add",
+                    clicked => Msg::Add,
+                },
+                gtk::Button {
+                    label: "Next turn",
+                    clicked => Msg::NextTurn,
+                },
+                gtk::Button {
+                    label: "End",
+                    clicked => Msg::End,
+                },
+                gtk::Button {
+                    label: "New",
+                    clicked => Msg::New,
+                },
+                #[name="group_initiative"]
+                gtk::CheckButton {
+                    label: "Group initiative",
+                    toggled => Msg::GroupInitiative,
+                },
+            },
+            #[name="order"]
+            gtk::Label {
+                halign: gtk::Align::Start,
+                use_markup: true,
+            },
+            #[name="prompt"]
+            gtk::Label {
+                halign: gtk::Align::Start,
+                use_markup: true,
+            },
+            gtk::Box {
+                spacing: 10,
+
+                #[name="target"]
+                gtk::ComboBoxText {},
+                #[name="amount"]
+                gtk::Entry {
+                    placeholder_text: Some("Amount"),
+                    width_chars: 6,
+                    activate => Msg::Damage,
+                },
+                gtk::Button {
+                    label: "Damage",
+                    clicked => Msg::Damage,
+                },
+                gtk::Button {
+                    label: "Damage group",
+                    clicked => Msg::DamageGroup,
+                },
+                gtk::Button {
+                    label: "Heal",
+                    clicked => Msg::Heal,
+                },
+                #[name="condition"]
+                gtk::ComboBoxText {},
+                #[name="rounds"]
+                gtk::Entry {
+                    placeholder_text: Some("Rounds"),
+                    width_chars: 6,
+                    activate => Msg::ApplyCondition,
+                },
+                gtk::Button {
+                    label: "Apply",
+                    clicked => Msg::ApplyCondition,
+                },
+                gtk::Button {
+                    label: "Remove",
+                    clicked => Msg::RemoveCondition,
+                },
+                #[name="legendary_cost"]
+                gtk::Entry {
+                    placeholder_text: Some("Cost"),
+                    width_chars: 4,
+                    activate => Msg::Legendary,
+                },
+                gtk::Button {
+                    label: "Legendary action",
+                    clicked => Msg::Legendary,
+                },
+            },
+            gtk::Box {
+                spacing: 10,
+
+                gtk::Label {
+                    text: "Mob attack",
+                },
+                #[name="mob_attackers"]
+                gtk::Entry {
+                    placeholder_text: Some("Attackers"),
+                    width_chars: 8,
+                    changed => Msg::Mob,
+                },
+                #[name="mob_bonus"]
+                gtk::Entry {
+                    placeholder_text: Some("Attack bonus"),
+                    width_chars: 8,
+                    changed => Msg::Mob,
+                },
+                #[name="mob_ac"]
+                gtk::Entry {
+                    placeholder_text: Some("AC"),
+                    width_chars: 4,
+                    changed => Msg::Mob,
+                },
+                #[name="mob_result"]
+                gtk::Label {},
+            },
+            gtk::ScrolledWindow {
+                min_content_height: 200,
+                vexpand: true,
+
+                #[name="log"]
+                gtk::TextView {
+                    editable: false,
+                    cursor_visible: false,
+                },
+            },
+            gtk::Box {
+                spacing: 10,
+
+                #[name="status"]
+                gtk::Label {
+                    halign: gtk::Align::Start,
+                    hexpand: true,
+                },
+                gtk::Button {
+                    label: "Export Markdown",
+                    clicked => Msg::ExportMarkdown,
+                },
+                gtk::Button {
+                    label: "Export JSON",
+                    clicked => Msg::ExportJson,
+                },
+            },
+        }
+    }
+}
+
+fn entry_text(entry: &gtk::Entry) -> String {
+    entry
+        .get_text()
+        .map(|t| t.trim().to_string())
+        .unwrap_or_default()
+}
+
+impl CombatView {
+    /// Index of the combatant selected as target.
+    fn target(&self) -> Option<usize> {
+        let index = self.target.get_active()? as usize;
+        if index < self.model.combat.combatants().len() {
+            Some(index)
+        } else {
+            None
+        }
+    }
+
+    /// Select the combatant with `id` as target again.
+    fn select_target(&self, id: Option<usize>) {
+        let index = id.and_then(|id| self.model.combat.position(id));
+        self.target.set_active(index.map(|i| i as u32));
+    }
+
+    fn update_view(&mut self) {
+        let combat = &self.model.combat;
+        let current = combat.current_turn();
+        let describe = |c: &Combatant| {
+            let conditions = c
+                .conditions
+                .iter()
+                .map(|a| a.condition.as_static())
+                .collect::<Vec<_>>()
+                .join(", ");
+            let conditions = glib::markup_escape_text(&conditions);
+            let legendary = match c.legendary_actions {
+                0 => String::new(),
+                n => format!("  {}/{} legendary", c.legendary_remaining, n),
+            };
+            format!(
+                "{:>3}  {}  {}/{} HP{}  {}",
+                c.initiative,
+                glib::markup_escape_text(&c.name),
+                c.hp,
+                c.max_hp,
+                legendary,
+                conditions
+            )
+        };
+        let order = combat
+            .turns()
+            .iter()
+            .map(|turn| {
+                let c = &combat.combatants()[turn.index()];
+                let line = match turn {
+                    Turn::Lair(_) => format!(
+                        "{:>3}  {} (lair)",
+                        LAIR_INITIATIVE,
+                        glib::markup_escape_text(&c.name)
+                    ),
+                    Turn::Group(_) => combat
+                        .members(*turn)
+                        .iter()
+                        .map(|i| describe(&combat.combatants()[*i]))
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                    Turn::Combatant(_) => describe(c),
+                };
+                if Some(*turn) == current {
+                    line.markup_bold()
+                } else {
+                    line
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let title = format!("Round {}", combat.round()).markup_bold();
+        self.order.set_markup(&format!("{}\n{}", title, order));
+
+        let prompt = combat
+            .legendary_available()
+            .iter()
+            .map(|i| &combat.combatants()[*i])
+            .map(|c| {
+                format!(
+                    "{} can take a legendary action ({} left)",
+                    glib::markup_escape_text(&c.name),
+                    c.legendary_remaining
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let prompt = if combat.is_started() {
+            prompt.markup_bold()
+        } else {
+            String::new()
+        };
+        self.prompt.set_markup(&prompt);
+
+        // Callers select the target again, as the indices may have moved.
+        self.target.remove_all();
+        for c in combat.combatants().iter() {
+            self.target.append_text(&c.name);
+        }
+
+        let log = combat
+            .log()
+            .entries()
+            .iter()
+            .map(|e| format!("[{}] Round {}: {}", e.time, e.round, e.action))
+            .collect::<Vec<_>>()
+            .join("\n");
+        if let Some(buffer) = self.log.get_buffer() {
+            buffer.set_text(&log);
+        }
+    }
+
+    /// Ask where to save `contents` and write it there.
+    fn export(&mut self, name: &str, contents: String) {
+        let dialog = gtk::FileChooserDialog::with_buttons(
+            Some("Export combat log"),
+            None::<&gtk::Window>,
+            gtk::FileChooserAction::Save,
+            &[
+                ("Cancel", gtk::ResponseType::Cancel),
+                ("Save", gtk::ResponseType::Accept),
+            ],
+        );
+        dialog.set_current_name(name);
+        dialog.set_do_overwrite_confirmation(true);
+        if dialog.run() == gtk::ResponseType::Accept.into() {
+            if let Some(path) = dialog.get_filename() {
+                let status = match fs::write(&path, contents) {
+                    Ok(()) => format!("Exported to {}", path.display()),
+                    Err(e) => format!("Could not export to {}: {}", path.display(), e),
+                };
+                self.status.set_text(&status);
+            }
+        }
+        dialog.destroy();
+    }
+}
@@ -1,23 +1,269 @@
-use relm::Widget;
+use gtk::{
+    ButtonExt, ComboBoxExtManual, ComboBoxTextExt, EditableSignals, EntryExt, LabelExt,
+    OrientableExt, WidgetExt,
+};
+use relm::{connect, connect_stream, Widget};
 use relm_derive::{widget, Msg};
+use std::cell::RefCell;
+use std::rc::Rc;
+use strum::AsStaticRef;
 
-pub struct Model {}
+use dm_tools::encounter::{ChallengeRating, Difficulty, Encounter, Monster};
+use dm_tools::table::EncounterTables;
+use dm_tools::time::Time;
+use dm_tools::ui::text::Markup;
+use dm_tools::ui::view::Header;
+use dm_tools::world::World;
+
+use crate::combat_view::CombatView;
+use crate::combat_view::Msg::Finished;
+
+/// Difficulties an encounter can be generated at.
+const DIFFICULTIES: [Difficulty; 4] = [
+    Difficulty::Easy,
+    Difficulty::Medium,
+    Difficulty::Hard,
+    Difficulty::Deadly,
+];
+
+pub struct Model {
+    world: Rc<RefCell<World>>,
+    encounter: Encounter,
+    tables: EncounterTables,
+    /// Why the user's tables weren't loaded, shown on init.
+    table_error: Option<String>,
+}
 
 #[derive(Msg)]
-pub enum Msg {}
+pub enum Msg {
+    SetParty,
+    AddMonster,
+    ClearMonsters,
+    Generate,
+    /// Emitted with the time a combat took, for the world to catch up.
+    Advance(Time),
+}
 
 #[widget]
 impl Widget for EncounterView {
-    fn model() -> Model {
-        Model {}
+    fn model(world: Rc<RefCell<World>>) -> Model {
+        let (tables, table_error) = match EncounterTables::load_all() {
+            Ok(tables) => (tables, None),
+            Err(e) => (
+                EncounterTables::bundled(),
+                Some(format!("Could not load your encounter tables: {}", e)),
+            ),
+        };
+        Model {
+            world,
+            encounter: Encounter::default(),
+            tables,
+            table_error,
+        }
+    }
+
+    fn init_view(&mut self) {
+        for terrain in self.model.tables.terrains() {
+            self.terrain.append_text(terrain);
+        }
+        self.terrain.set_active(Some(0));
+        for difficulty in DIFFICULTIES.iter() {
+            self.difficulty.append_text(difficulty.as_static());
+        }
+        self.difficulty.set_active(Some(1));
+        if let Some(ref error) = self.model.table_error {
+            self.generate_status.set_text(error);
+        }
+        self.update_summary();
     }
 
     fn update(&mut self, event: Msg) {
-        match event {}
+        match event {
+            Msg::SetParty => {
+                let text = self
+                    .party
+                    .get_text()
+                    .map(|t| t.to_string())
+                    .unwrap_or_default();
+                self.model.encounter.party = text
+                    .split(|c: char| c == ',' || c.is_whitespace())
+                    .filter_map(|level| level.parse::<u8>().ok())
+                    .filter(|level| (1..=20).contains(level))
+                    .collect();
+            }
+            Msg::AddMonster => {
+                if let Some(monster) = self.monster() {
+                    self.model.encounter.monsters.push(monster);
+                    self.monster_name.set_text("");
+                    self.monster_cr.set_text("");
+                    self.monster_count.set_text("");
+                }
+            }
+            Msg::ClearMonsters => self.model.encounter.monsters.clear(),
+            Msg::Generate => {
+                let terrain = self.terrain.get_active_text().map(|t| t.to_string());
+                let difficulty = self
+                    .difficulty
+                    .get_active()
+                    .and_then(|i| DIFFICULTIES.get(i as usize));
+                if let (Some(terrain), Some(difficulty)) = (terrain, difficulty) {
+                    let party = self.model.encounter.party.clone();
+                    let generated = self.model.tables.generate(
+                        &terrain,
+                        &party,
+                        *difficulty,
+                        &mut rand::thread_rng(),
+                    );
+                    let status = match generated {
+                        Some(encounter) => {
+                            self.model.encounter.monsters = encounter.monsters;
+                            String::new()
+                        }
+                        None => format!(
+                            "No {} encounter in the {} tables fits this party",
+                            difficulty.as_static(),
+                            terrain
+                        ),
+                    };
+                    self.generate_status.set_text(&status);
+                }
+            }
+            // For the parent.
+            Msg::Advance(_) => {}
+        }
+        self.update_summary();
     }
 
     view! {
         gtk::Box {
+            orientation: gtk::Orientation::Vertical,
+            spacing: 10,
+
+            Header("Encounter") {},
+            gtk::Box {
+                spacing: 10,
+                margin_start: 10,
+                margin_end: 10,
+
+                gtk::Label {
+                    text: "Party levels",
+                },
+                #[name="party"]
+                gtk::Entry {
+                    hexpand: true,
+                    placeholder_text: Some("3, 3, 4, 5"),
+                    changed => Msg::SetParty,
+                },
+            },
+            gtk::Box {
+                spacing: 10,
+                margin_start: 10,
+                margin_end: 10,
+
+                #[name="monster_name"]
+                gtk::Entry {
+                    hexpand: true,
+                    placeholder_text: Some("Monster"),
+                    activate => Msg::AddMonster,
+                },
+                #[name="monster_cr"]
+                gtk::Entry {
+                    placeholder_text: Some("CR"),
+                    width_chars: 4,
+                    activate => Msg::AddMonster,
+                },
+                #[name="monster_count"]
+                gtk::Entry {
+                    input_purpose: gtk::InputPurpose::Number,
+                    placeholder_text: Some("1"),
+                    width_chars: 4,
+                    activate => Msg::AddMonster,
+                },
+                gtk::Button {
+                    label: "Add",
+                    clicked => Msg::AddMonster,
+                },
+                gtk::Button {
+                    label: "Clear",
+                    clicked => Msg::ClearMonsters,
+                },
+            },
+            gtk::Box {
+                spacing: 10,
+                margin_start: 10,
+                margin_end: 10,
+
+                #[name="terrain"]
+                gtk::ComboBoxText {},
+                #[name="difficulty"]
+                gtk::ComboBoxText {},
+                gtk::Button {
+                    label: "Generate",
+                    clicked => Msg::Generate,
+                },
+                #[name="generate_status"]
+                gtk::Label {},
+            },
+            #[name="monsters"]
+            gtk::Label {
+                halign: gtk::Align::Start,
+                margin_start: 10,
+            },
+            #[name="summary"]
+            gtk::Label {
+                use_markup: true,
+            },
+            Header("Combat") {},
+            CombatView(self.model.world.clone()) {
+                Finished(ref duration) => Msg::Advance(duration.clone()),
+            },
         }
     }
 }
+
+impl EncounterView {
+    /// Build a monster from the entry fields, if they are valid.
+    fn monster(&self) -> Option<Monster> {
+        let text = |entry: &gtk::Entry| entry.get_text().map(|t| t.to_string()).unwrap_or_default();
+        let name = text(&self.monster_name);
+        let cr = text(&self.monster_cr).parse::<ChallengeRating>().ok()?;
+        let count = match text(&self.monster_count).trim() {
+            "" => 1,
+            count => count.parse::<usize>().ok().filter(|c| *c > 0)?,
+        };
+        Some(Monster::new(name.trim(), cr, count))
+    }
+
+    fn update_summary(&mut self) {
+        let encounter = &self.model.encounter;
+        let monsters = encounter
+            .monsters
+            .iter()
+            .map(|m| format!("{} x{} ({} XP)", m.name, m.count, m.xp()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.monsters.set_text(&monsters);
+
+        let thresholds = [
+            Difficulty::Easy,
+            Difficulty::Medium,
+            Difficulty::Hard,
+            Difficulty::Deadly,
+        ]
+        .iter()
+        .map(|d| format!("{}: {}", d.as_static(), encounter.threshold(*d)))
+        .collect::<Vec<_>>()
+        .join("  ");
+
+        let text = format!(
+            "{}\n{}\nBase XP: {}  Multiplier: x{}  Adjusted XP: {}\nXP per character: {}",
+            encounter.difficulty().as_static().markup_title(),
+            thresholds,
+            encounter.base_xp(),
+            encounter.multiplier(),
+            encounter.adjusted_xp(),
+            encounter.xp_per_character(),
+        );
+        self.summary.set_markup(&text);
+    }
+}
@@ -1,59 +1,141 @@
+mod character_view;
+mod combat_view;
 mod encounter_view;
 mod time_view;
+mod treasure_view;
 
-use gtk::{Inhibit, NotebookExt, WidgetExt};
-use relm::{connect, connect_stream, Widget};
+use gtk::{Inhibit, NotebookExt, OverlayExt, WidgetExt};
+use relm::{connect, connect_stream, Component, Widget};
 use relm_derive::{widget, Msg};
+use std::cell::RefCell;
+use std::rc::Rc;
 
+use dm_tools::keys::Action;
+use dm_tools::time::{Event, Time};
+use dm_tools::ui::keys::{self, ShortcutsHelp};
+use dm_tools::world::World;
+
+use character_view::CharacterView;
+use character_view::Msg::TimeChanged;
 use encounter_view::EncounterView;
+use encounter_view::Msg::Advance as CombatEnded;
+use time_view::Msg::{Advance as AdvanceBy, TimelineChanged};
 use time_view::TimeView;
+use treasure_view::TreasureView;
 
 use self::Msg::*;
 
 #[derive(Msg)]
 pub enum Msg {
+    Shortcut(Option<Action>),
+    Advance(Time),
+    /// World time changed, with these events starting.
+    Moved(Vec<Event>),
+    /// Show the new world time wherever it's displayed.
+    Refresh,
     Quit,
 }
 
-pub struct Model {}
+pub struct Model {
+    /// Shared by every tab that reads or moves time.
+    world: Rc<RefCell<World>>,
+    help: Option<Component<ShortcutsHelp>>,
+}
 
 #[widget]
 impl Widget for Win {
     fn model() -> Model {
-        Model {}
+        Model {
+            world: Rc::new(RefCell::new(World::new())),
+            help: None,
+        }
+    }
+
+    fn init_view(&mut self) {
+        let help = relm::init::<ShortcutsHelp>(()).expect("ShortcutsHelp init failed");
+        help.widget().set_no_show_all(true);
+        help.widget().hide();
+        self.overlay.add_overlay(help.widget());
+        self.model.help = Some(help);
     }
 
     fn update(&mut self, event: Msg) {
         match event {
+            Shortcut(Some(action)) => self.shortcut(action),
+            Shortcut(None) => {}
+            Advance(duration) => {
+                // A single step, so events see every second of it.
+                let started = self.model.world.borrow_mut().advance(&duration);
+                self.update(Moved(started));
+            }
+            Moved(started) => {
+                self.characters
+                    .stream()
+                    .emit(character_view::Msg::Advanced(started.clone()));
+                self.time.stream().emit(time_view::Msg::Started(started));
+            }
+            Refresh => self.time.stream().emit(time_view::Msg::Refresh),
             Quit => gtk::main_quit(),
         }
     }
 
     view! {
         gtk::Window {
-            gtk::Notebook{
-                #[name="tabs"]
-                TimeView {
-                    child: {
-                        tab_label: Some("Time")
-                    }
-                },
-                EncounterView {
-                    child: {
-                        tab_label: Some("Encounter")
-                    }
-                },
-                gtk::Box {
-                    child: {
-                        tab_label: Some("Character")
-                    }
+            #[name="overlay"]
+            gtk::Overlay {
+                #[name="notebook"]
+                gtk::Notebook{
+                    #[name="time"]
+                    TimeView(self.model.world.clone()) {
+                        AdvanceBy(ref duration) => Advance(duration.clone()),
+                        TimelineChanged(ref started) => Moved(started.clone()),
+                        child: {
+                            tab_label: Some("Time")
+                        }
+                    },
+                    EncounterView(self.model.world.clone()) {
+                        CombatEnded(ref duration) => Advance(duration.clone()),
+                        child: {
+                            tab_label: Some("Encounter")
+                        }
+                    },
+                    #[name="characters"]
+                    CharacterView(self.model.world.clone()) {
+                        TimeChanged => Refresh,
+                        child: {
+                            tab_label: Some("Character")
+                        }
+                    },
+                    TreasureView {
+                        child: {
+                            tab_label: Some("Treasure")
+                        }
+                    },
                 },
             },
+            key_press_event(_, event) => (Shortcut(keys::action(event)), Inhibit(keys::action(event).is_some())),
             delete_event(_, _) => (Quit, Inhibit(false)),
         }
     }
 }
 
+impl Win {
+    fn shortcut(&mut self, action: Action) {
+        match action {
+            Action::Advance(seconds) => self.update(Advance(Time { value: seconds })),
+            Action::Tab(page) => self.notebook.set_current_page(Some(page)),
+            Action::NextTab => self.notebook.next_page(),
+            Action::PreviousTab => self.notebook.prev_page(),
+            Action::Help => {
+                if let Some(ref help) = self.model.help {
+                    let widget = help.widget();
+                    widget.set_visible(!widget.get_visible());
+                }
+            }
+        }
+    }
+}
+
 fn main() {
     Win::run(()).expect("Win::run failed");
 }
@@ -1,79 +1,676 @@
-use gtk::{OrientableExt, WidgetExt};
-use relm::Widget;
+use gtk::{
+    BoxExt, ButtonExt, ComboBoxExt, ComboBoxTextExt, ContainerExt, EntryExt, FlowBoxExt, LabelExt,
+    ListBoxExt, OrientableExt, RevealerExt, ScrolledWindowExt, ToggleButtonExt, WidgetExt,
+};
+use relm::{connect, connect_stream, Relm, Widget};
 use relm_derive::{widget, Msg};
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::rc::Rc;
 
+use dm_tools::calendar::{Calendar, Date};
+use dm_tools::preset::{self, Preset, Presets};
+use dm_tools::time::{Category, Event, Time, UnitTime};
+use dm_tools::ui::calendar::CalendarView;
+use dm_tools::ui::calendar::Msg::{Mark, Selected as DaySelected, SetTime as CalendarSetTime};
 use dm_tools::ui::clock::Clock;
+use dm_tools::ui::clock::Msg::{Changed as ClockChanged, Set as ClockSet};
+use dm_tools::ui::count::UnitCounterMsg::Changed;
+use dm_tools::ui::count::{CounterOptions, UnitCounterMsg, UnitCounterView};
 use dm_tools::ui::view::Header;
+use dm_tools::unit::{Unit, UnitCounter};
+use dm_tools::world::World;
 
-pub struct Model {}
+/// Units a new preset's length is entered in.
+const PRESET_UNITS: [UnitTime; 3] = [UnitTime::Minute, UnitTime::Hour, UnitTime::Day];
+
+/// Units events are extended by, down to rounds of effects.
+const EXTEND_UNITS: [UnitTime; 4] = [
+    UnitTime::Second,
+    UnitTime::Minute,
+    UnitTime::Hour,
+    UnitTime::Day,
+];
+
+pub struct Model {
+    relm: Relm<TimeView>,
+    world: Rc<RefCell<World>>,
+    presets: Presets,
+    /// Length of the next preset the user adds.
+    duration: UnitCounter<UnitTime>,
+    calendar: Calendar,
+    /// Day clicked on the calendar.
+    day: Option<Date>,
+    /// Length of the next event added on `day`.
+    event_duration: UnitCounter<UnitTime>,
+    /// Categories shown in the agenda, ticked or not.
+    filters: Vec<(Category, gtk::CheckButton)>,
+    /// How much the agenda's Extend buttons add.
+    extend_by: UnitCounter<UnitTime>,
+    /// Length of the next timer started.
+    timer_duration: UnitCounter<UnitTime>,
+    /// Why the user's presets or calendar weren't loaded, shown on init.
+    preset_error: Option<String>,
+    calendar_error: Option<String>,
+}
 
 #[derive(Msg)]
-pub enum Msg {}
+pub enum Msg {
+    /// World time moved on elsewhere, show it.
+    Refresh,
+    SetTime(Time),
+    Duration(UnitCounter<UnitTime>),
+    AddPreset,
+    RemovePreset,
+    SelectDay(Date),
+    JumpToDay,
+    EventDuration(UnitCounter<UnitTime>),
+    AddEvent,
+    Filter,
+    ExtendBy(UnitCounter<UnitTime>),
+    Extend(u64),
+    Cancel(u64),
+    /// Time moved on elsewhere with these events starting, show them.
+    Started(Vec<Event>),
+    DismissNotice,
+    TimerDuration(UnitCounter<UnitTime>),
+    StartTimer,
+    Diverge,
+    SwitchTimeline,
+    SyncTimeline,
+    /// Emitted after switching or syncing timelines, with the events that
+    /// started.
+    TimelineChanged(Vec<Event>),
+    /// Emitted to move world time forward, by presets and clock edits alike.
+    Advance(Time),
+}
 
 #[widget]
 impl Widget for TimeView {
-    fn model() -> Model {
-        Model {}
+    fn model(relm: &Relm<Self>, world: Rc<RefCell<World>>) -> Model {
+        let (presets, preset_error) = match Presets::load() {
+            Ok(presets) => (presets, None),
+            Err(e) => (
+                Presets::default(),
+                Some(format!("Could not load your presets: {}", e)),
+            ),
+        };
+        let (calendar, calendar_error) = match Calendar::load() {
+            Ok(calendar) => (calendar, None),
+            Err(e) => (
+                Calendar::default(),
+                Some(format!(
+                    "Could not load the calendar, using the default: {}",
+                    e
+                )),
+            ),
+        };
+        Model {
+            relm: relm.clone(),
+            world,
+            presets,
+            duration: UnitCounter::new_with_units(&PRESET_UNITS),
+            calendar,
+            day: None,
+            event_duration: UnitCounter::new_with_units(&PRESET_UNITS),
+            filters: Vec::new(),
+            extend_by: UnitCounter::new_with_units(&EXTEND_UNITS),
+            timer_duration: UnitCounter::new_with_units(&PRESET_UNITS),
+            preset_error,
+            calendar_error,
+        }
+    }
+
+    fn init_view(&mut self) {
+        for category in Category::variants().iter().cloned() {
+            self.event_category.append_text(&category.to_string());
+            let check = gtk::CheckButton::new_with_label(&category.to_string());
+            check.set_active(true);
+            connect!(self.model.relm, check, connect_toggled(_), Msg::Filter);
+            self.filters.add(&check);
+            self.model.filters.push((category, check));
+        }
+        self.event_category.set_active(Some(0));
+        self.filters.show_all();
+        self.update_presets();
+        if let Some(ref error) = self.model.preset_error {
+            self.preset_status.set_text(error);
+        }
+        if let Some(ref error) = self.model.calendar_error {
+            self.day.set_text(error);
+        }
+        self.model.relm.stream().emit(Msg::Refresh);
     }
 
     fn update(&mut self, event: Msg) {
-        match event {}
+        match event {
+            Msg::Refresh => {
+                let time = self.model.world.borrow().time().clone();
+                self.clock.stream().emit(ClockSet(time.clone()));
+                self.calendar.stream().emit(CalendarSetTime(time));
+                self.update_events();
+                self.update_timelines();
+            }
+            Msg::SetTime(time) => {
+                let now = self.model.world.borrow().time().value;
+                if time.value != now {
+                    let duration = Time {
+                        value: time.value - now,
+                    };
+                    self.model.relm.stream().emit(Msg::Advance(duration));
+                }
+            }
+            Msg::Duration(duration) => self.model.duration = duration,
+            Msg::AddPreset => {
+                let seconds = self.model.duration.total();
+                if seconds <= 0 {
+                    return self
+                        .preset_status
+                        .set_text("Give the preset a length first");
+                }
+                let duration = Time { value: seconds };
+                let name = match entry_text(&self.preset_name) {
+                    name if name.is_empty() => self.model.duration.to_string(),
+                    name => name,
+                };
+                self.model.presets.add(Preset::new(&name, duration));
+                self.save_presets(&format!("Added {}", name));
+                self.preset_name.set_text("");
+                self.model.duration = UnitCounter::new_with_units(&PRESET_UNITS);
+                self.preset_duration
+                    .stream()
+                    .emit(UnitCounterMsg::Set(self.model.duration.clone()));
+            }
+            Msg::RemovePreset => {
+                let name = entry_text(&self.preset_name);
+                match self.model.presets.remove(&name) {
+                    Some(_) => self.save_presets(&format!("Removed {}", name)),
+                    None => self
+                        .preset_status
+                        .set_text(&format!("No preset of yours is named \"{}\"", name)),
+                }
+            }
+            Msg::SelectDay(date) => {
+                self.model.day = Some(date);
+                self.day.set_text(&self.model.calendar.describe(&date));
+                self.day_actions.set_sensitive(true);
+            }
+            Msg::JumpToDay => {
+                if let Some(time) = self.day_time() {
+                    self.model.relm.stream().emit(Msg::SetTime(time));
+                }
+            }
+            Msg::EventDuration(duration) => self.model.event_duration = duration,
+            Msg::AddEvent => {
+                let name = entry_text(&self.event_name);
+                let start = match self.day_time() {
+                    Some(start) => start,
+                    None => return,
+                };
+                if name.is_empty() {
+                    return self.day.set_text("Name the event first");
+                }
+                let end = Time {
+                    value: start.value + self.model.event_duration.total(),
+                };
+                let category = self
+                    .event_category
+                    .get_active()
+                    .and_then(|i| Category::variants().get(i as usize))
+                    .copied()
+                    .unwrap_or(Category::Other);
+                self.model
+                    .world
+                    .borrow_mut()
+                    .scheduler
+                    .schedule(&name, category, start, end);
+                let date = self.model.calendar.describe(&Date::from_time(&start));
+                self.day
+                    .set_text(&format!("{} scheduled for {}", name, date));
+                self.event_name.set_text("");
+                self.update_events();
+            }
+            Msg::Filter => self.update_agenda(),
+            Msg::ExtendBy(duration) => self.model.extend_by = duration,
+            Msg::Extend(id) => {
+                let seconds = self.model.extend_by.total();
+                if seconds <= 0 {
+                    return self
+                        .agenda_status
+                        .set_text("Set how long to extend by first");
+                }
+                let duration = Time { value: seconds };
+                if self
+                    .model
+                    .world
+                    .borrow_mut()
+                    .scheduler
+                    .extend(id, &duration)
+                {
+                    self.agenda_status
+                        .set_text(&format!("Extended by {}", self.model.extend_by));
+                }
+                self.update_events();
+            }
+            Msg::Cancel(id) => {
+                let cancelled = self.model.world.borrow_mut().scheduler.cancel(id);
+                if let Some(event) = cancelled.first() {
+                    self.agenda_status
+                        .set_text(&format!("Cancelled {}", event.name));
+                }
+                self.update_events();
+            }
+            Msg::Started(started) => {
+                if !started.is_empty() {
+                    let now = self.model.world.borrow().time().clone();
+                    let lines: Vec<String> = started
+                        .iter()
+                        .map(|event| match event.category {
+                            Category::Timer => event.countdown(&now),
+                            _ => format!("{} started", event.name),
+                        })
+                        .collect();
+                    self.notice_text.set_text(&lines.join("\n"));
+                    self.notice.set_reveal_child(true);
+                }
+                self.model.relm.stream().emit(Msg::Refresh);
+            }
+            Msg::DismissNotice => self.notice.set_reveal_child(false),
+            Msg::TimerDuration(duration) => self.model.timer_duration = duration,
+            Msg::StartTimer => {
+                let name = entry_text(&self.timer_name);
+                let seconds = self.model.timer_duration.total();
+                if name.is_empty() || seconds <= 0 {
+                    return self
+                        .timeline_status
+                        .set_text("Give the timer a name and a length first");
+                }
+                self.model
+                    .world
+                    .borrow_mut()
+                    .start_timer(&name, &Time { value: seconds });
+                self.timeline_status.set_text(&format!(
+                    "{} completes in {}",
+                    name, self.model.timer_duration
+                ));
+                self.timer_name.set_text("");
+                self.update_events();
+            }
+            Msg::Diverge => {
+                let name = entry_text(&self.timeline_name);
+                if name.is_empty() {
+                    return self.timeline_status.set_text("Name the timeline first");
+                }
+                let result = self.model.world.borrow_mut().diverge(&name);
+                match result {
+                    Ok(()) => {
+                        self.timeline_status
+                            .set_text(&format!("Split off {}", name));
+                        self.timeline_name.set_text("");
+                    }
+                    Err(e) => self.timeline_status.set_text(&e.to_string()),
+                }
+                self.update_timelines();
+            }
+            Msg::SwitchTimeline => {
+                let name = match self.other_timeline() {
+                    Some(name) => name,
+                    None => return self.timeline_status.set_text("Split off a timeline first"),
+                };
+                let result = self.model.world.borrow_mut().switch_to(&name);
+                match result {
+                    Ok(()) => self
+                        .model
+                        .relm
+                        .stream()
+                        .emit(Msg::TimelineChanged(Vec::new())),
+                    Err(e) => self.timeline_status.set_text(&e.to_string()),
+                }
+            }
+            Msg::SyncTimeline => {
+                let name = match self.other_timeline() {
+                    Some(name) => name,
+                    None => return self.timeline_status.set_text("Split off a timeline first"),
+                };
+                let result = self.model.world.borrow_mut().sync(&name);
+                match result {
+                    Ok(started) => {
+                        self.timeline_status
+                            .set_text(&format!("{} caught up", name));
+                        self.model.relm.stream().emit(Msg::TimelineChanged(started));
+                    }
+                    Err(e) => self.timeline_status.set_text(&e.to_string()),
+                }
+            }
+            // For the parent.
+            Msg::TimelineChanged(_) | Msg::Advance(_) => {}
+        }
     }
 
     view! {
-        ClockView {},
-    }
-}
+        gtk::Box {
+            spacing: 40,
 
-pub struct ClockModel {}
+            gtk::Box {
+                orientation: gtk::Orientation::Vertical,
+                valign: gtk::Align::Center,
+                spacing: 20,
 
-#[derive(Msg)]
-pub enum ClockMsg {}
+                gtk::Frame {
+                    halign: gtk::Align::Center,
 
-#[widget]
-impl Widget for ClockView {
-    fn model() -> ClockModel {
-        ClockModel {}
-    }
+                    #[name="clock"]
+                    Clock {
+                        ClockChanged(ref time) => Msg::SetTime(time.clone()),
+                    },
+                },
+                Header("Advance") {},
+                #[name="presets"]
+                gtk::FlowBox {
+                    selection_mode: gtk::SelectionMode::None,
+                    halign: gtk::Align::Center,
+                    max_children_per_line: 8,
+                },
+                gtk::Box {
+                    halign: gtk::Align::Center,
+                    spacing: 10,
 
-    fn update(&mut self, event: ClockMsg) {
-        match event {}
-    }
+                    #[name="preset_name"]
+                    gtk::Entry {
+                        placeholder_text: Some("Preset name"),
+                        activate => Msg::AddPreset,
+                    },
+                    #[name="preset_duration"]
+                    UnitCounterView<UnitTime>(CounterOptions::with_units(&PRESET_UNITS).carry()) {
+                        Changed(ref duration) => Msg::Duration(duration.clone()),
+                    },
+                    gtk::Button {
+                        label: "Add preset",
+                        clicked => Msg::AddPreset,
+                    },
+                    gtk::Button {
+                        label: "Remove preset",
+                        clicked => Msg::RemovePreset,
+                    },
+                },
+                #[name="preset_status"]
+                gtk::Label {},
+            },
+            gtk::Box {
+                orientation: gtk::Orientation::Vertical,
+                valign: gtk::Align::Center,
+                spacing: 10,
 
-    view! {
-        gtk::Frame {
-            halign: gtk::Align::Center,
-            valign: gtk::Align::Center,
+                Header("Calendar") {},
+                #[name="calendar"]
+                CalendarView(self.model.calendar.clone()) {
+                    DaySelected(ref date) => Msg::SelectDay(*date),
+                },
+                #[name="day"]
+                gtk::Label {
+                    text: "Click a day to jump to it or add an event",
+                },
+                #[name="day_actions"]
+                gtk::Box {
+                    orientation: gtk::Orientation::Vertical,
+                    spacing: 5,
+                    sensitive: false,
+
+                    gtk::Button {
+                        label: "Jump to this day",
+                        clicked => Msg::JumpToDay,
+                    },
+                    #[name="event_name"]
+                    gtk::Entry {
+                        placeholder_text: Some("Event name"),
+                        activate => Msg::AddEvent,
+                    },
+                    #[name="event_category"]
+                    gtk::ComboBoxText {},
+                    UnitCounterView<UnitTime>(CounterOptions::with_units(&PRESET_UNITS).carry()) {
+                        Changed(ref duration) => Msg::EventDuration(duration.clone()),
+                    },
+                    gtk::Button {
+                        label: "Add event",
+                        clicked => Msg::AddEvent,
+                    },
+                },
+            },
+            gtk::Box {
+                orientation: gtk::Orientation::Vertical,
+                spacing: 10,
+
+                #[name="notice"]
+                gtk::Revealer {
+                    gtk::Box {
+                        spacing: 10,
+
+                        #[name="notice_text"]
+                        gtk::Label {
+                            hexpand: true,
+                        },
+                        gtk::Button {
+                            label: "Dismiss",
+                            clicked => Msg::DismissNotice,
+                        },
+                    },
+                },
+                Header("Timelines") {},
+                #[name="timeline"]
+                gtk::Label {},
+                gtk::Box {
+                    spacing: 5,
+
+                    #[name="timeline_name"]
+                    gtk::Entry {
+                        placeholder_text: Some("New timeline"),
+                        activate => Msg::Diverge,
+                    },
+                    gtk::Button {
+                        label: "Split off",
+                        clicked => Msg::Diverge,
+                    },
+                    #[name="other_timelines"]
+                    gtk::ComboBoxText {},
+                    gtk::Button {
+                        label: "Switch",
+                        clicked => Msg::SwitchTimeline,
+                    },
+                    gtk::Button {
+                        label: "Sync",
+                        clicked => Msg::SyncTimeline,
+                    },
+                },
+                gtk::Box {
+                    spacing: 5,
 
-            Clock {}
+                    #[name="timer_name"]
+                    gtk::Entry {
+                        placeholder_text: Some("Timer name"),
+                        activate => Msg::StartTimer,
+                    },
+                    UnitCounterView<UnitTime>(CounterOptions::with_units(&PRESET_UNITS).carry()) {
+                        Changed(ref duration) => Msg::TimerDuration(duration.clone()),
+                    },
+                    gtk::Button {
+                        label: "Start timer",
+                        clicked => Msg::StartTimer,
+                    },
+                },
+                #[name="timeline_status"]
+                gtk::Label {},
+                Header("Agenda") {},
+                #[name="filters"]
+                gtk::Box {
+                    spacing: 5,
+                },
+                gtk::ScrolledWindow {
+                    min_content_height: 200,
+                    vexpand: true,
+                    hscrollbar_policy: gtk::PolicyType::Never,
+
+                    #[name="agenda"]
+                    gtk::ListBox {
+                        selection_mode: gtk::SelectionMode::None,
+                    },
+                },
+                gtk::Box {
+                    spacing: 10,
+
+                    gtk::Label {
+                        text: "Extend by",
+                    },
+                    UnitCounterView<UnitTime>(CounterOptions::with_units(&EXTEND_UNITS).carry()) {
+                        Changed(ref duration) => Msg::ExtendBy(duration.clone()),
+                    },
+                },
+                #[name="agenda_status"]
+                gtk::Label {},
+            },
         }
     }
 }
 
-pub struct CalendarModel {}
+impl TimeView {
+    /// One button per preset, each advancing by its duration in a single step.
+    fn update_presets(&mut self) {
+        for child in self.presets.get_children().iter() {
+            self.presets.remove(child);
+        }
+        for preset in self.model.presets.all() {
+            let button = gtk::Button::new_with_label(&preset.name);
+            let duration = preset.duration;
+            connect!(
+                self.model.relm,
+                button,
+                connect_clicked(_),
+                Msg::Advance(duration.clone())
+            );
+            self.presets.insert(&button, -1);
+        }
+        self.presets.show_all();
+    }
 
-#[derive(Msg)]
-pub enum CalendarMsg {}
+    /// The clicked day at the current time of day.
+    fn day_time(&self) -> Option<Time> {
+        let day = self.model.day?;
+        let now = self.model.world.borrow().time().value;
+        Some(Time {
+            value: day.start().value + now.rem_euclid(UnitTime::Day.value()),
+        })
+    }
 
-#[widget]
-impl Widget for CalendarView {
-    fn model() -> CalendarModel {
-        CalendarModel {}
+    /// Show the timeline being played and list the others to switch to.
+    fn update_timelines(&mut self) {
+        let world = self.model.world.borrow();
+        self.timeline
+            .set_text(&format!("Playing {} at {}", world.timeline, world.time()));
+        self.other_timelines.remove_all();
+        for timeline in world.timelines.iter() {
+            self.other_timelines.append_text(&timeline.name);
+        }
+        if !world.timelines.is_empty() {
+            self.other_timelines.set_active(Some(0));
+        }
     }
 
-    fn update(&mut self, event: CalendarMsg) {
-        match event {}
+    fn other_timeline(&self) -> Option<String> {
+        self.other_timelines
+            .get_active_text()
+            .map(|name| name.to_string())
     }
 
-    view! {
-        gtk::Box {
-            orientation: gtk::Orientation::Vertical,
-            valign: gtk::Align::Center,
+    fn update_events(&mut self) {
+        self.update_marks();
+        self.update_agenda();
+    }
 
-            Header("Calendar") {},
-            gtk::Calendar {
-                valign: gtk::Align::Center,
-            },
+    /// Mark every day with a scheduled event.
+    fn update_marks(&mut self) {
+        let marked: BTreeSet<Date> = self
+            .model
+            .world
+            .borrow()
+            .scheduler
+            .events()
+            .iter()
+            .flat_map(Date::days_of)
+            .collect();
+        self.calendar.stream().emit(Mark(marked));
+    }
+
+    /// List the events yet to end in the ticked categories, with a countdown
+    /// to each.
+    fn update_agenda(&mut self) {
+        for child in self.agenda.get_children().iter() {
+            self.agenda.remove(child);
+        }
+        let shown: Vec<Category> = self
+            .model
+            .filters
+            .iter()
+            .filter(|(_, check)| check.get_active())
+            .map(|(category, _)| *category)
+            .collect();
+        let world = self.model.world.borrow();
+        let now = world.time();
+        let events: Vec<_> = world
+            .scheduler
+            .agenda()
+            .into_iter()
+            .filter(|e| shown.contains(&e.category))
+            .collect();
+        if events.is_empty() {
+            self.agenda
+                .insert(&gtk::Label::new(Some("Nothing scheduled")), -1);
         }
+        for event in events {
+            let row = gtk::Box::new(gtk::Orientation::Horizontal, 5);
+            let text = format!("{} ({})", event.countdown(now), event.category);
+            let label = gtk::Label::new(Some(text.as_str()));
+            label.set_halign(gtk::Align::Start);
+            label.set_hexpand(true);
+            row.add(&label);
+
+            let jump = gtk::Button::new_with_label("Jump to start");
+            jump.set_sensitive(event.start != *now);
+            let (id, start) = (event.id, event.start.clone());
+            connect!(
+                self.model.relm,
+                jump,
+                connect_clicked(_),
+                Msg::SetTime(start.clone())
+            );
+            row.add(&jump);
+            let extend = gtk::Button::new_with_label("Extend");
+            connect!(self.model.relm, extend, connect_clicked(_), Msg::Extend(id));
+            row.add(&extend);
+            let cancel = gtk::Button::new_with_label("Cancel");
+            connect!(self.model.relm, cancel, connect_clicked(_), Msg::Cancel(id));
+            row.add(&cancel);
+            self.agenda.insert(&row, -1);
+        }
+        self.agenda.show_all();
+    }
+
+    fn save_presets(&mut self, done: &str) {
+        let result = match preset::config_path() {
+            Some(path) => self.model.presets.save(path).map_err(|e| e.to_string()),
+            None => Err("no config directory".to_string()),
+        };
+        match result {
+            Ok(()) => self.preset_status.set_text(done),
+            Err(e) => self
+                .preset_status
+                .set_text(&format!("{}, but it won't be kept: {}", done, e)),
+        }
+        self.update_presets();
     }
 }
+
+fn entry_text(entry: &gtk::Entry) -> String {
+    entry
+        .get_text()
+        .map(|t| t.trim().to_string())
+        .unwrap_or_default()
+}
@@ -0,0 +1,132 @@
+use gtk::{BoxExt, ButtonExt, EntryExt, LabelExt, OrientableExt, ToggleButtonExt, WidgetExt};
+use relm::Widget;
+use relm_derive::{widget, Msg};
+
+use dm_tools::coin::{format_coins, parse_coins, Purse};
+use dm_tools::treasure::{split, Share, SplitMode};
+use dm_tools::ui::purse::PurseView;
+use dm_tools::ui::view::Header;
+use dm_tools::unit::CountError;
+
+pub struct Model {}
+
+#[derive(Msg)]
+pub enum Msg {
+    Split,
+}
+
+#[widget]
+impl Widget for TreasureView {
+    fn model() -> Model {
+        Model {}
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Msg::Split => {
+                let text = match self.split() {
+                    Ok(text) => text,
+                    Err(e) => format!(
+                        "Can't split: {}. Enter a hoard like \"120 gp 35 sp\" and shares like \"Tordek, Mialee:2\"",
+                        e
+                    ),
+                };
+                self.result.set_text(&text);
+            }
+        }
+    }
+
+    view! {
+        gtk::Box {
+            orientation: gtk::Orientation::Vertical,
+            spacing: 10,
+
+            Header("Purse") {},
+            gtk::Frame {
+                halign: gtk::Align::Center,
+
+                PurseView {},
+            },
+            Header("Split treasure") {},
+            gtk::Box {
+                spacing: 10,
+                margin_start: 10,
+                margin_end: 10,
+
+                #[name="hoard"]
+                gtk::Entry {
+                    placeholder_text: Some("Hoard, e.g. 120 gp 35 sp"),
+                    hexpand: true,
+                    activate => Msg::Split,
+                },
+                #[name="shares"]
+                gtk::Entry {
+                    placeholder_text: Some("Shares, e.g. Tordek, Mialee:2"),
+                    hexpand: true,
+                    activate => Msg::Split,
+                },
+                #[name="fund"]
+                gtk::Entry {
+                    placeholder_text: Some("Fund shares"),
+                    width_chars: 4,
+                    activate => Msg::Split,
+                },
+                #[name="convert"]
+                gtk::CheckButton {
+                    label: "Convert to fewest coins",
+                },
+                gtk::Button {
+                    label: "Split",
+                    clicked => Msg::Split,
+                },
+            },
+            #[name="result"]
+            gtk::Label {
+                halign: gtk::Align::Start,
+                margin_start: 10,
+            },
+        }
+    }
+}
+
+fn entry_text(entry: &gtk::Entry) -> String {
+    entry
+        .get_text()
+        .map(|t| t.trim().to_string())
+        .unwrap_or_default()
+}
+
+impl TreasureView {
+    fn split(&self) -> Result<String, CountError> {
+        let mut hoard = Purse::new();
+        for (coin, count) in parse_coins(&entry_text(&self.hoard))? {
+            hoard.add_coins(count, coin)?;
+        }
+
+        let mut shares = entry_text(&self.shares)
+            .split(',')
+            .map(|share| share.parse::<Share>())
+            .collect::<Result<Vec<_>, _>>()?;
+        match entry_text(&self.fund).as_str() {
+            "" | "0" => {}
+            weight => shares.push(Share::fund(
+                weight.parse().map_err(|e| CountError::invalid(weight, e))?,
+            )),
+        }
+
+        let mode = if self.convert.get_active() {
+            SplitMode::FewestCoins
+        } else {
+            SplitMode::NoConversion
+        };
+        let split = split(&hoard, &shares, mode);
+
+        let mut lines: Vec<String> = split
+            .shares
+            .iter()
+            .map(|(share, coins)| format!("{}: {}", share.name, format_coins(coins)))
+            .collect();
+        lines.push(format!("Left over: {}", format_coins(&split.remainder)));
+        Ok(lines.join("\n"))
+    }
+}